@@ -0,0 +1,491 @@
+//! Procedural macros for `bevy_event_set`
+//!
+//! These are re-exported from `bevy_event_set`, use them from there.
+
+use std::collections::{HashMap, HashSet};
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::token::Bracket;
+use syn::{
+	bracketed, parse_macro_input, Error, Expr, Fields, GenericParam, Ident, ItemStruct, Path,
+	PathArguments, Token, Type, TypePath,
+};
+
+/// An entry in the attribute arguments: an event type, optionally renamed
+/// with `as`, or a nested event set prefixed with `..`
+enum EventType {
+	Event {
+		ty: Box<Type>,
+		variant: Option<(Token![as], Ident)>,
+	},
+	Nested {
+		dots: Token![..],
+		path: Path,
+	},
+}
+
+impl Parse for EventType {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		if input.peek(Token![..]) {
+			return Ok(EventType::Nested {
+				dots: input.parse()?,
+				path: input.call(Path::parse_mod_style)?,
+			});
+		}
+
+		let ty = Box::new(input.parse()?);
+		let variant = if input.peek(Token![as]) {
+			Some((input.parse()?, input.parse()?))
+		} else {
+			None
+		};
+
+		Ok(EventType::Event { ty, variant })
+	}
+}
+
+/// The arguments of the `events` attribute: the event types, the stage as
+/// `stage = expr`, and the path of `bevy_event_set` as `crate = path` if the
+/// crate was renamed
+struct Args {
+	krate: Option<Path>,
+	stage: Option<Expr>,
+	events: Vec<EventType>,
+}
+
+impl Parse for Args {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let mut krate = None;
+		let mut stage = None;
+		let mut events = Vec::new();
+		while !input.is_empty() {
+			if input.peek(Token![crate]) && input.peek2(Token![=]) {
+				let token = input.parse::<Token![crate]>()?;
+				if krate.is_some() {
+					return Err(Error::new(
+						token.span,
+						"the crate path can only be given once",
+					));
+				}
+				input.parse::<Token![=]>()?;
+				krate = Some(input.call(Path::parse_mod_style)?);
+			} else if input.peek(Ident) && input.peek2(Token![=]) {
+				let name = input.parse::<Ident>()?;
+				if name != "stage" {
+					return Err(Error::new(
+						name.span(),
+						format!("unknown argument `{}`, expected `crate` or `stage`", name),
+					));
+				}
+				if stage.is_some() {
+					return Err(Error::new(name.span(), "the stage can only be given once"));
+				}
+				input.parse::<Token![=]>()?;
+				stage = Some(input.parse()?);
+			} else {
+				events.push(input.parse()?);
+			}
+
+			if !input.is_empty() {
+				input.parse::<Token![,]>()?;
+			}
+		}
+
+		Ok(Args {
+			krate,
+			stage,
+			events,
+		})
+	}
+}
+
+/// Turns a unit struct into an event set
+///
+/// See `bevy_event_set::events` for the documentation.
+#[proc_macro_attribute]
+pub fn events(attr: TokenStream, item: TokenStream) -> TokenStream {
+	let args = parse_macro_input!(attr as Args);
+	let item = parse_macro_input!(item as ItemStruct);
+
+	match expand(args, item) {
+		Ok(tokens) => tokens.into(),
+		Err(err) => err.to_compile_error().into(),
+	}
+}
+
+fn expand(args: Args, item: ItemStruct) -> syn::Result<TokenStream2> {
+	if args.events.is_empty() {
+		return Err(Error::new_spanned(
+			&item.ident,
+			"cannot make an empty event set",
+		));
+	}
+
+	if !matches!(item.fields, Fields::Unit) {
+		return Err(Error::new_spanned(
+			&item.fields,
+			"event sets must be unit structs, their fields are generated from the event types",
+		));
+	}
+
+	// The generated items already have lifetime parameters, and the bounds in a
+	// where clause would be lost by the `SystemParam` derive
+	let mut params = Vec::new();
+	for param in &item.generics.params {
+		match param {
+			GenericParam::Type(param) if param.default.is_some() => {
+				return Err(Error::new_spanned(
+					param,
+					"the type parameters of event sets cannot have defaults",
+				));
+			}
+			GenericParam::Type(param) => params.push(param),
+			GenericParam::Lifetime(param) => {
+				return Err(Error::new_spanned(
+					param,
+					"event sets cannot have lifetime parameters",
+				));
+			}
+			GenericParam::Const(param) => {
+				return Err(Error::new_spanned(
+					param,
+					"event sets cannot have const parameters",
+				));
+			}
+		}
+	}
+	if let Some(where_clause) = &item.generics.where_clause {
+		return Err(Error::new_spanned(
+			where_clause,
+			"event sets cannot have a where clause, put the bounds on the type parameters",
+		));
+	}
+	let type_args = params.iter().map(|param| &param.ident);
+
+	let mut cfgs = Vec::new();
+	let mut attrs = Vec::new();
+	for attr in item.attrs {
+		if attr.path.is_ident("cfg") {
+			cfgs.push(attr.parse_args::<TokenStream2>()?);
+		} else if attr.path.is_ident("stage") {
+			return Err(Error::new_spanned(
+				&attr,
+				"give the stage in the events attribute instead, as `stage = ..`",
+			));
+		} else {
+			attrs.push(attr);
+		}
+	}
+
+	let events = &args.events;
+	let stage = &args.stage;
+	let krate = match args.krate {
+		Some(krate) => quote!(#krate),
+		None => quote!(::bevy_event_set),
+	};
+	let vis = &item.vis;
+	let name = &item.ident;
+	Ok(quote! {
+		#krate::event_set!(@item [cfg(all(#(#cfgs),*))] [#(#attrs)*] [#stage] [[#(#params,)*] [#(#type_args,)*]] #vis #name { #(#events),* });
+	})
+}
+
+/// The input of `unique_events!`: `[callback] [args]` and the event types of the set, in the
+/// same form as in `event_set!`
+struct UniqueEvents {
+	callback: TokenStream2,
+	args: TokenStream2,
+	entries: Vec<Entry>,
+}
+
+/// An event type of a set as it was written, or a member of a nested set as `[variant: type]`
+enum Entry {
+	Listed(EventType),
+	Member { variant: Ident, ty: Box<Type> },
+}
+
+/// A generated field of an event set
+///
+/// The variant is `None` for types that weren't given a variant name with `as`
+/// and aren't a plain path that the name can be taken from.
+struct Member {
+	field: Ident,
+	variant: Option<Ident>,
+	ty: Type,
+}
+
+impl Parse for UniqueEvents {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let callback;
+		bracketed!(callback in input);
+		let args;
+		bracketed!(args in input);
+
+		let mut entries = Vec::new();
+		while !input.is_empty() {
+			if input.peek(Bracket) {
+				let member;
+				bracketed!(member in input);
+				let variant = member.parse()?;
+				member.parse::<Token![:]>()?;
+				let ty = Box::new(member.parse()?);
+				entries.push(Entry::Member { variant, ty });
+			} else {
+				entries.push(Entry::Listed(input.parse()?));
+			}
+
+			if !input.is_empty() {
+				input.parse::<Token![,]>()?;
+			}
+		}
+
+		Ok(UniqueEvents {
+			callback: callback.parse()?,
+			args: args.parse()?,
+			entries,
+		})
+	}
+}
+
+impl ToTokens for EventType {
+	fn to_tokens(&self, tokens: &mut TokenStream2) {
+		match self {
+			EventType::Event {
+				ty,
+				variant: Some((as_token, variant)),
+			} => tokens.extend(quote!(#ty #as_token #variant)),
+			EventType::Event { ty, variant: None } => ty.to_tokens(tokens),
+			EventType::Nested { dots, path } => tokens.extend(quote!(#dots #path)),
+		}
+	}
+}
+
+impl ToTokens for Entry {
+	fn to_tokens(&self, tokens: &mut TokenStream2) {
+		match self {
+			Entry::Listed(event) => event.to_tokens(tokens),
+			Entry::Member { variant, ty } => tokens.extend(quote!([#variant: #ty])),
+		}
+	}
+}
+
+/// Names the fields and variants of an event set and checks that they are unique
+///
+/// This is used by `bevy_event_set::event_set!` with the event types of a set.
+/// Nested sets are replaced by their members one at a time, by calling the
+/// members macro of the nested set, which calls `unique_events!` again. When
+/// all types are known and there are no duplicates, it calls
+/// `callback! { args [field variant: type]... }`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn unique_events(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as UniqueEvents);
+
+	match expand_unique(input) {
+		Ok(tokens) => tokens.into(),
+		Err(err) => err.to_compile_error().into(),
+	}
+}
+
+fn expand_unique(input: UniqueEvents) -> syn::Result<TokenStream2> {
+	let UniqueEvents {
+		callback,
+		args,
+		entries,
+	} = input;
+
+	let nested = entries
+		.iter()
+		.position(|entry| matches!(entry, Entry::Listed(EventType::Nested { .. })));
+	if let Some(index) = nested {
+		let (before, after) = (&entries[..index], &entries[index + 1..]);
+		let mut path = match &entries[index] {
+			Entry::Listed(EventType::Nested { path, .. }) => path.clone(),
+			_ => unreachable!(),
+		};
+		let last = path.segments.last_mut().unwrap();
+		last.ident = format_ident!("__{}_members", last.ident);
+		return Ok(quote!(#path! { [#callback] [#args] [#(#before,)*] [#(#after),*] }));
+	}
+
+	let members = members(entries)?;
+	check_unique(&members)?;
+	let members = members
+		.iter()
+		.map(|Member { field, variant, ty }| quote!([#field #variant: #ty]));
+	Ok(quote!(#callback! { #args #(#members)* }))
+}
+
+/// Gives each event type a field name, and a variant name if it has one
+fn members(entries: Vec<Entry>) -> syn::Result<Vec<Member>> {
+	let mut members = Vec::new();
+	for (index, entry) in entries.into_iter().enumerate() {
+		if index == 64 {
+			return Err(Error::new_spanned(
+				entry,
+				"cannot make an event set with more than 64 event types",
+			));
+		}
+
+		let (variant, ty) = match entry {
+			Entry::Listed(EventType::Event {
+				ty,
+				variant: Some((_, variant)),
+			}) => (Some(variant), *ty),
+			Entry::Listed(EventType::Event { ty, variant: None }) => (last_segment(&ty), *ty),
+			Entry::Listed(EventType::Nested { .. }) => unreachable!(),
+			Entry::Member { variant, ty } => (Some(variant), *ty),
+		};
+		members.push(Member {
+			field: format_ident!("_{}", index),
+			variant,
+			ty,
+		});
+	}
+
+	Ok(members)
+}
+
+/// Returns the name of a type that is a path without type arguments
+fn last_segment(ty: &Type) -> Option<Ident> {
+	match ungroup(ty) {
+		Type::Path(TypePath { qself: None, path }) => {
+			let last = path.segments.last()?;
+			match last.arguments {
+				PathArguments::None => Some(last.ident.clone()),
+				_ => None,
+			}
+		}
+		_ => None,
+	}
+}
+
+fn check_unique(members: &[Member]) -> syn::Result<()> {
+	let mut types = HashSet::new();
+	let mut variants = HashMap::new();
+
+	for member in members {
+		let ty = ungroup(&member.ty);
+		let name: String = quote!(#ty).to_string().split_whitespace().collect();
+		let name = name.trim_start_matches("self::").to_string();
+		if !types.insert(name.clone()) {
+			return Err(Error::new_spanned(
+				ty,
+				format!(
+					"event type `{}` appears more than once in this event set",
+					name
+				),
+			));
+		}
+
+		let variant = match &member.variant {
+			Some(variant) => variant,
+			None => {
+				return Err(Error::new_spanned(
+					ty,
+					format!(
+						"event type `{}` needs a variant name, add `as` and a name after it",
+						name
+					),
+				))
+			}
+		};
+
+		if let Some(other) = variants.insert(variant.to_string(), name.clone()) {
+			return Err(Error::new_spanned(
+				variant,
+				format!(
+					"event types `{}` and `{}` both get the variant name `{}`, use `as` to rename one",
+					other, name, variant,
+				),
+			));
+		}
+	}
+
+	Ok(())
+}
+
+/// Removes the invisible groups around a type that was passed through `macro_rules!`
+fn ungroup(ty: &Type) -> &Type {
+	match ty {
+		Type::Group(group) => ungroup(&group.elem),
+		ty => ty,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn check(input: &str) -> Result<(), String> {
+		let input: UniqueEvents = syn::parse_str(input).unwrap();
+		members(input.entries)
+			.and_then(|members| check_unique(&members))
+			.map_err(|err| err.to_string())
+	}
+
+	#[test]
+	fn duplicate_type() {
+		assert_eq!(
+			check("[callback] [] Jump, Score, Jump as Jump2"),
+			Err("event type `Jump` appears more than once in this event set".into()),
+		);
+		assert_eq!(
+			check("[callback] [] input::Jump, [Jump2: self::input::Jump]"),
+			Err("event type `input::Jump` appears more than once in this event set".into()),
+		);
+	}
+
+	#[test]
+	fn duplicate_variant() {
+		assert_eq!(
+			check("[callback] [] input::Jump, net::Jump"),
+			Err("event types `input::Jump` and `net::Jump` both get the variant name `Jump`, use `as` to rename one".into()),
+		);
+	}
+
+	#[test]
+	fn unnamed() {
+		assert_eq!(
+			check("[callback] [] Jump, Packet<Ping>"),
+			Err(
+				"event type `Packet<Ping>` needs a variant name, add `as` and a name after it"
+					.into()
+			),
+		);
+		assert_eq!(check("[callback] [] Jump, Packet<Ping> as Ping"), Ok(()));
+	}
+
+	#[test]
+	fn too_many() {
+		let events: Vec<_> = (0..65).map(|i| format!("E{}", i)).collect();
+		assert_eq!(
+			check(&format!("[callback] [] {}", events[..64].join(", "))),
+			Ok(())
+		);
+		assert_eq!(
+			check(&format!("[callback] [] {}", events.join(", "))),
+			Err("cannot make an event set with more than 64 event types".into()),
+		);
+	}
+
+	#[test]
+	fn args() {
+		let args: Args = syn::parse_str("Jump, stage = stage::LAST, Score").unwrap();
+		assert_eq!(args.events.len(), 2);
+		assert!(args.stage.is_some());
+
+		let error = |input| syn::parse_str::<Args>(input).err().unwrap().to_string();
+		assert_eq!(
+			error("stage = stage::LAST, Jump, stage = stage::FIRST"),
+			"the stage can only be given once"
+		);
+		assert_eq!(
+			error("Jump, stages = stage::LAST"),
+			"unknown argument `stages`, expected `crate` or `stage`"
+		);
+	}
+}
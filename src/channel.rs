@@ -0,0 +1,81 @@
+//! Sending events into an event set from other threads
+//!
+//! See [`EventSetSender`] for the documentation.
+
+use std::sync::mpsc::{self, Receiver, SendError, Sender};
+use std::sync::Mutex;
+
+/// Sends events into an event set from outside the ECS
+///
+/// The sender can be cloned and moved to other threads. Events sent through it
+/// are added to the event set at the start of the next frame, in the order they
+/// were sent. Get one through
+/// [`ResourcesEventSetExt::event_set_sender`](crate::ResourcesEventSetExt::event_set_sender).
+///
+/// # Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct Connected;
+/// struct Message(String);
+/// event_set!(NetworkEvents { Connected, Message });
+///
+/// let mut app = App::build();
+/// app.add_event_set::<NetworkEvents>();
+///
+/// let sender = app.resources().event_set_sender::<NetworkEvents>();
+/// std::thread::spawn(move || {
+///     sender.send(Connected).ok();
+///     sender.send(Message("hello".to_string())).ok();
+/// });
+/// ```
+pub struct EventSetSender<A> {
+	sender: Sender<A>,
+}
+
+impl<A> EventSetSender<A> {
+	pub(crate) fn new(sender: Sender<A>) -> Self {
+		EventSetSender { sender }
+	}
+
+	/// Sends an event into the event set
+	///
+	/// This fails if the app that owns the event set has been dropped.
+	pub fn send<T: Into<A>>(&self, event: T) -> Result<(), SendError<A>> {
+		self.sender.send(event.into())
+	}
+}
+
+impl<A> Clone for EventSetSender<A> {
+	fn clone(&self) -> Self {
+		EventSetSender::new(self.sender.clone())
+	}
+}
+
+/// Receives the events sent through the [`EventSetSender`]s of the event set with sum enum `A`
+#[doc(hidden)]
+pub struct EventSetChannel<A> {
+	sender: Mutex<Sender<A>>,
+	receiver: Mutex<Receiver<A>>,
+}
+
+impl<A> Default for EventSetChannel<A> {
+	fn default() -> Self {
+		let (sender, receiver) = mpsc::channel();
+		EventSetChannel {
+			sender: Mutex::new(sender),
+			receiver: Mutex::new(receiver),
+		}
+	}
+}
+
+impl<A> EventSetChannel<A> {
+	pub fn sender(&self) -> EventSetSender<A> {
+		EventSetSender::new(self.sender.lock().unwrap().clone())
+	}
+
+	pub fn drain(&self) -> Vec<A> {
+		self.receiver.lock().unwrap().try_iter().collect()
+	}
+}
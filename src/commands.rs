@@ -0,0 +1,126 @@
+//! Event sets that send their events through [`Commands`]
+//!
+//! See [`CommandsEventSetExt`] for the documentation.
+
+use crate::__private::{SendAnyToResources, SendToResources};
+use crate::{EventSet, SendAnyEvent, SendEvent};
+use bevy::app::Events;
+use bevy::ecs::{Command, Commands, Component, Resources, World};
+use std::marker::PhantomData;
+
+/// Trait used to add deferred event sending to the Bevy commands
+///
+/// Events sent through commands are written to the event buffers when the
+/// commands are applied. A system that sends events this way doesn't need
+/// write access to the event buffers, so it can run in parallel with the
+/// systems that read them.
+///
+/// # Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct EventOne;
+/// struct EventTwo;
+/// event_set!(MyEvents { EventOne, EventTwo });
+///
+/// fn event_emitter_system(commands: &mut Commands) {
+///     commands.send_event(EventOne);
+///
+///     let mut events = commands.event_set::<MyEvents>();
+///     events.send(EventOne);
+///     events.send(EventTwo);
+/// }
+/// ```
+pub trait CommandsEventSetExt {
+	/// Sends an event when the commands are applied
+	///
+	/// This writes to the event buffer directly, so the event doesn't get a
+	/// place in the send order of an event set.
+	fn send_event<T: Component>(&mut self, event: T) -> &mut Self;
+
+	/// Borrows an event set that sends its events when the commands are applied
+	fn event_set<S: EventSet>(&mut self) -> EventSetCommands<'_, S>;
+}
+
+impl CommandsEventSetExt for Commands {
+	fn send_event<T: Component>(&mut self, event: T) -> &mut Self {
+		self.add_command(SendEvents {
+			events: event,
+			send: |resources, event: T| {
+				resources
+					.get_mut::<Events<T>>()
+					.unwrap_or_else(|| {
+						panic!(
+							"no event buffer for `{}`, was the event added to the app?",
+							std::any::type_name::<T>()
+						)
+					})
+					.send(event);
+			},
+		})
+	}
+
+	fn event_set<S: EventSet>(&mut self) -> EventSetCommands<'_, S> {
+		EventSetCommands {
+			commands: self,
+			marker: PhantomData,
+		}
+	}
+}
+
+/// An event set that sends its events when the commands are applied
+///
+/// Get one through [`CommandsEventSetExt::event_set`].
+pub struct EventSetCommands<'c, S> {
+	commands: &'c mut Commands,
+	marker: PhantomData<fn() -> S>,
+}
+
+impl<'c, S: SendToResources<T>, T: Component> SendEvent<T> for EventSetCommands<'c, S> {
+	fn send(&mut self, event: T) {
+		self.send_batch(Some(event));
+	}
+
+	fn send_batch<E: IntoIterator<Item = T>>(&mut self, events: E) {
+		self.commands.add_command(SendEvents {
+			events: events.into_iter().collect::<Vec<T>>(),
+			send: S::send_batch::<Vec<T>>,
+		});
+	}
+}
+
+impl<'c, S: SendToResources<T>, T: Component> Extend<T> for EventSetCommands<'c, S> {
+	fn extend<E: IntoIterator<Item = T>>(&mut self, events: E) {
+		self.send_batch(events);
+	}
+}
+
+impl<'c, S: SendAnyToResources> SendAnyEvent for EventSetCommands<'c, S>
+where
+	S::Any: Component,
+{
+	type Any = S::Any;
+
+	fn send_any(&mut self, event: S::Any) {
+		self.commands.add_command(SendEvents {
+			events: event,
+			send: S::send_any,
+		});
+	}
+}
+
+/// Sends events to the resources when the commands are applied
+///
+/// The send function is a plain function pointer so the command doesn't hold
+/// on to the lifetime of the event set type.
+struct SendEvents<E> {
+	events: E,
+	send: fn(&Resources, E),
+}
+
+impl<E: Component> Command for SendEvents<E> {
+	fn write(self: Box<Self>, _world: &mut World, resources: &mut Resources) {
+		(self.send)(resources, self.events);
+	}
+}
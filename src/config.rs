@@ -0,0 +1,224 @@
+//! Configuration for how the event buffers of an event set are updated
+//!
+//! See [`EventSetConfig`] for the documentation.
+
+use bevy::app::{stage, AppBuilder, Events};
+use bevy::ecs::{Component, IntoSystem, Local, Res, ResMut};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Configures how the event buffers of an event set are updated
+///
+/// Use it with [`AddEventSet::add_event_set_with`](crate::AddEventSet::add_event_set_with).
+/// The default configuration works like `add_event`.
+///
+/// An event type is only registered once, so if it's in several event sets,
+/// the configuration of the first set that is added to the app is used.
+///
+/// # Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct EventOne;
+/// struct EventTwo;
+/// event_set!(MyEvents { EventOne, EventTwo });
+///
+/// App::build().add_event_set_with::<MyEvents>(EventSetConfig {
+///     retention: Retention::Frames(4),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSetConfig {
+	/// How long the events are kept in their buffers
+	pub retention: Retention,
+
+	/// The name of a stage to update the buffers in lock-step with
+	///
+	/// Use this with a stage that doesn't run every frame, such as a fixed
+	/// timestep stage. The buffers are then only updated after that stage has
+	/// run, and at most once per frame, so the systems in that stage and the
+	/// systems that run every frame each see all events. With
+	/// [`Retention::Frames`], the number counts the runs of the stage.
+	pub lock_step: Option<&'static str>,
+
+	/// The name of the stage where the buffers are updated
+	///
+	/// If this isn't set, the stage from the `#[stage(..)]` attribute of the
+	/// event set is used, or `stage::EVENT` if it doesn't have one.
+	pub stage: Option<&'static str>,
+}
+
+impl Default for EventSetConfig {
+	fn default() -> Self {
+		EventSetConfig {
+			retention: Retention::Frames(1),
+			lock_step: None,
+			stage: None,
+		}
+	}
+}
+
+/// How long the events of an event set are kept in their buffers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Retention {
+	/// The buffers are updated every `n` frames, so events are kept for at
+	/// least `n` and at most `2 * n` frames
+	///
+	/// `Frames(1)` is the default behaviour of Bevy events. Adding an event set
+	/// with `Frames(0)` panics.
+	Frames(u32),
+
+	/// The buffers are only updated once a reader of the event set has read
+	/// the events, so events aren't dropped before they are seen
+	///
+	/// Only readers created by the event set count, reading the events
+	/// through an `EventReader` doesn't update the buffers.
+	UntilRead,
+
+	/// The buffers are never updated automatically, call
+	/// [`Events.update`](bevy::app::Events::update()) or
+	/// [`Events.clear`](bevy::app::Events::clear()) to remove old events
+	Manual,
+}
+
+/// Adds an event type to the app, unless another event set already added it
+#[doc(hidden)]
+pub fn add_event<T: Component>(app: &mut AppBuilder, config: &EventSetConfig) {
+	if config.retention == Retention::Frames(0) {
+		panic!("events can't be kept for 0 frames, use `Retention::Frames(1)` to keep them for one frame");
+	}
+
+	if app.resources().contains::<Events<T>>() {
+		return;
+	}
+
+	let update_stage = config.stage.unwrap_or(stage::EVENT);
+	let frames = match config.retention {
+		Retention::Frames(1) if config.lock_step.is_none() => {
+			app.add_resource(Events::<T>::default())
+				.add_system_to_stage(update_stage, Events::<T>::update_system.system());
+			return;
+		}
+		Retention::Frames(frames) => frames,
+		Retention::UntilRead => {
+			app.add_resource(ReadMark::<T>::default());
+			1
+		}
+		Retention::Manual => {
+			app.add_resource(Events::<T>::default());
+			return;
+		}
+	};
+
+	let until_read = config.retention == Retention::UntilRead;
+	let lock_step = config.lock_step;
+	if let Some(stage) = lock_step {
+		add_lock_step(app, stage);
+	}
+
+	let update = move |mut events: ResMut<Events<T>>,
+	                   read: Option<Res<ReadMark<T>>>,
+	                   steps: Option<Res<LockSteps>>,
+	                   mut state: Local<UpdateState>| {
+		// With a lock-step stage, the frames are counted in runs of that stage
+		let step = lock_step.map(|stage| steps.as_ref().map_or(0, |steps| steps.get(stage)));
+		let runs = step.map_or(1, |step| step - state.step);
+		if runs == 0 {
+			return;
+		}
+		if until_read && !matches!(&read, Some(read) if read.is_read()) {
+			return;
+		}
+
+		if let Some(step) = step {
+			state.step = step;
+		}
+		if let Some(read) = read {
+			read.clear();
+		}
+
+		state.frames += runs;
+		if state.frames >= u64::from(frames) {
+			state.frames = 0;
+			events.update();
+		}
+	};
+
+	app.add_resource(Events::<T>::default())
+		.add_system_to_stage(update_stage, update.system());
+}
+
+/// Keeps track of an event buffer between its updates
+#[derive(Default)]
+struct UpdateState {
+	frames: u64,
+	step: u64,
+}
+
+/// Counts how many times each lock-step stage has run
+#[derive(Default)]
+struct LockSteps(HashMap<&'static str, u64>);
+
+impl LockSteps {
+	fn get(&self, stage: &str) -> u64 {
+		self.0.get(stage).copied().unwrap_or(0)
+	}
+}
+
+/// Adds a system to a lock-step stage that counts how many times it has run
+fn add_lock_step(app: &mut AppBuilder, stage: &'static str) {
+	if !app.resources().contains::<LockSteps>() {
+		app.add_resource(LockSteps::default());
+	}
+
+	let added = app
+		.resources()
+		.get_mut::<LockSteps>()
+		.unwrap()
+		.0
+		.insert(stage, 0)
+		.is_none();
+	if added {
+		let step = move |mut steps: ResMut<LockSteps>| {
+			*steps.0.entry(stage).or_insert(0) += 1;
+		};
+		app.add_system_to_stage(stage, step.system());
+	}
+}
+
+/// Records that a reader of an event set has read the events of type `T`
+#[doc(hidden)]
+pub struct ReadMark<T> {
+	read: AtomicBool,
+	marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for ReadMark<T> {
+	fn default() -> Self {
+		ReadMark {
+			read: AtomicBool::new(false),
+			marker: PhantomData,
+		}
+	}
+}
+
+impl<T> ReadMark<T> {
+	fn is_read(&self) -> bool {
+		self.read.load(Ordering::Relaxed)
+	}
+
+	fn clear(&self) {
+		self.read.store(false, Ordering::Relaxed);
+	}
+}
+
+/// Marks the events of type `T` as read, if they are kept until they are read
+#[doc(hidden)]
+pub fn mark_read<T>(mark: Option<&ReadMark<T>>) {
+	if let Some(mark) = mark {
+		mark.read.store(true, Ordering::Relaxed);
+	}
+}
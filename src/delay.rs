@@ -0,0 +1,54 @@
+//! Sending the events of an event set after a delay
+
+use std::time::{Duration, Instant};
+
+/// Holds the events of the event set with sum enum `A` that were sent with a delay
+pub struct PendingEvents<A> {
+	frame: u64,
+	pending: Vec<(Due, A)>,
+}
+
+enum Due {
+	Frame(u64),
+	Time(Instant),
+}
+
+impl<A> Default for PendingEvents<A> {
+	fn default() -> Self {
+		PendingEvents {
+			frame: 0,
+			pending: Vec::new(),
+		}
+	}
+}
+
+impl<A> PendingEvents<A> {
+	pub fn after(&mut self, event: A, delay: Duration) {
+		self.pending
+			.push((Due::Time(Instant::now() + delay), event));
+	}
+
+	pub fn after_frames(&mut self, event: A, frames: u32) {
+		self.pending
+			.push((Due::Frame(self.frame + u64::from(frames)), event));
+	}
+
+	/// Moves on to the next frame and takes out the events that are due, in the order they were sent
+	pub fn release(&mut self) -> Vec<A> {
+		self.frame += 1;
+		if self.pending.is_empty() {
+			return Vec::new();
+		}
+
+		let frame = self.frame;
+		let now = Instant::now();
+		let (due, pending): (Vec<_>, Vec<_>) =
+			self.pending.drain(..).partition(|(due, _)| match due {
+				Due::Frame(due) => *due <= frame,
+				Due::Time(due) => *due <= now,
+			});
+
+		self.pending = pending;
+		due.into_iter().map(|(_, event)| event).collect()
+	}
+}
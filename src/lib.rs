@@ -6,7 +6,8 @@
 //! ```
 //!
 //! This will create a struct that can be used to send events of all given
-//! types through the [`SendEvent`] trait.
+//! types through the [`SendEvent`] trait, and a companion `[name]Reader`
+//! struct that can be used to read them through the [`ReadEvent`] trait.
 //!
 //! # Example
 //! ```
@@ -33,6 +34,23 @@
 //! fn event_two_listener_system(events: Res<Events<EventTwo>>) { }
 //! fn event_three_listener_system(events: Res<Events<EventThree>>) { }
 //!
+//! // Or read several types of the set in one system
+//! fn event_listener_system(mut events: MyEventsReader) {
+//!     for EventThree(n) in events.iter::<EventThree>() { }
+//!     if let Some(EventOne) = events.latest::<EventOne>() { }
+//! }
+//!
+//! // Or read all events of the set in the order they were sent
+//! fn event_log_system(mut events: MyEventsReader) {
+//!     for event in events.iter_all() {
+//!         match event {
+//!             MyEventsRef::EventOne(_) => println!("one"),
+//!             MyEventsRef::EventTwo(_) => println!("two"),
+//!             MyEventsRef::EventThree(EventThree(n)) => println!("three: {}", n),
+//!         }
+//!     }
+//! }
+//!
 //! // Add the event set to your app
 //! App::build()
 //!     .add_event_set::<MyEvents>();
@@ -40,9 +58,111 @@
 
 use bevy::app::AppBuilder;
 
+// Lets the `events` attribute refer to this crate by name in its own tests
+extern crate self as bevy_event_set;
+
+/// Turns a unit struct into an event set
+///
+/// This creates the same items as the [`event_set!`] macro, but keeps the doc
+/// comments, visibility and other attributes of the struct.
+///
+/// # Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct EventOne;
+/// struct EventTwo;
+///
+/// /// Events sent by the input systems
+/// #[events(EventOne, EventTwo)]
+/// pub(crate) struct MyEvents;
+///
+/// fn event_emitter_system(mut events: MyEvents) {
+///     events.send(EventOne);
+///     events.send(EventTwo);
+/// }
+///
+/// App::build().add_event_set::<MyEvents>();
+/// ```
+///
+/// The stage that the buffers are updated in can be given as `stage = ..`,
+/// like the `#[stage(..)]` attribute of [`event_set!`]:
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct Collision;
+///
+/// #[events(Collision, stage = stage::LAST)]
+/// struct PhysicsEvents;
+/// ```
+///
+/// The struct can have type parameters, as long as their bounds are written
+/// on the parameters instead of in a where clause. Every parameter has to be
+/// used by an event type, and needs the `Send + Sync + 'static` bounds that
+/// event types need:
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct Packet<T>(T);
+/// struct Ping;
+///
+/// #[events(Packet<T> as Packet)]
+/// struct NetEvents<T: Send + Sync + 'static>;
+///
+/// fn ping_system(mut events: NetEvents<Ping>) {
+///     events.send(Packet(Ping));
+/// }
+///
+/// App::build().add_event_set::<NetEvents<Ping>>();
+/// ```
+///
+/// If this crate is renamed in `Cargo.toml`, pass its new name as
+/// `#[events(crate = new_name, ..)]`.
+pub use bevy_event_set_macros::events;
+
+pub use channel::EventSetSender;
+pub use commands::{CommandsEventSetExt, EventSetCommands};
+pub use config::{EventSetConfig, Retention};
+pub use log::EventSetLogPlugin;
+pub use only::{Only, OrderMode, Ordered, Unordered};
+pub use probe::{EventSetProbe, ProbeOrder};
+#[cfg(feature = "serde")]
+pub use record::{EventSetRecorder, RecordFormat, Recorded};
+#[cfg(feature = "serde")]
+pub use replay::EventSetReplay;
+pub use resources::{EventSetMut, ResourcesEventSetExt};
+pub use tuple::{EventSetOf, EventTuple};
+
+pub mod channel;
+pub mod commands;
+pub mod config;
+mod delay;
+pub mod log;
+pub mod only;
+mod order;
+pub mod probe;
+#[cfg(feature = "serde")]
+pub mod record;
+#[cfg(feature = "serde")]
+pub mod replay;
+pub mod resources;
+mod suppress;
+pub mod tuple;
+
 /// Describes an event set
 pub trait EventSet {
-	fn apply(app: &mut AppBuilder);
+	/// Adds the event types of the set to the app with the default configuration
+	fn apply(app: &mut AppBuilder) {
+		Self::apply_with(app, &EventSetConfig::default());
+	}
+
+	/// Adds the event types of the set to the app with the given configuration
+	fn apply_with(app: &mut AppBuilder, config: &EventSetConfig);
 }
 
 /// Trait used to add `add_event_set` to the Bevy app builder
@@ -60,6 +180,14 @@ pub trait AddEventSet {
 	/// App::build().add_event_set::<MyEventSet>();
 	/// ```
 	fn add_event_set<E: EventSet>(&mut self) -> &mut Self;
+
+	/// Adds an event set to the app with a custom configuration
+	///
+	/// See [`EventSetConfig`] for the options.
+	fn add_event_set_with<E: EventSet>(&mut self, config: EventSetConfig) -> &mut Self;
+
+	/// Adds an event set to the app, with its buffers updated in the given stage
+	fn add_event_set_in_stage<E: EventSet>(&mut self, stage: &'static str) -> &mut Self;
 }
 
 impl AddEventSet for AppBuilder {
@@ -67,84 +195,2434 @@ impl AddEventSet for AppBuilder {
 		E::apply(self);
 		self
 	}
+
+	fn add_event_set_with<E: EventSet>(&mut self, config: EventSetConfig) -> &mut Self {
+		E::apply_with(self, &config);
+		self
+	}
+
+	fn add_event_set_in_stage<E: EventSet>(&mut self, stage: &'static str) -> &mut Self {
+		self.add_event_set_with::<E>(EventSetConfig {
+			stage: Some(stage),
+			..Default::default()
+		})
+	}
 }
 
 /// Allows an event set to send an event of a given type
-pub trait SendEvent<T> {
+///
+/// The `I` parameter is only used by [`EventSetOf`], where it marks the
+/// position of `T` in the tuple. Sets made with [`event_set!`] leave it out.
+pub trait SendEvent<T, I = ()> {
 	/// Sends an event to the event buffer
 	///
 	/// Calls [`Events.send`](bevy::app::Events::send()) on the Bevy event buffer of the corresponding type.
 	fn send(&mut self, event: T);
+
+	/// Sends several events of the same type to the event buffer
+	///
+	/// Event sets made with [`event_set!`] record the send order of the whole
+	/// batch at once, which is faster than calling [`send`](Self::send()) for
+	/// each event. Generated sets also implement [`Extend`], which does the same.
+	fn send_batch<E>(&mut self, events: E)
+	where
+		E: IntoIterator<Item = T>,
+		Self: Sized,
+	{
+		for event in events {
+			self.send(event);
+		}
+	}
+}
+
+/// Allows an event set to send an event of any of its types through its
+/// generated `[name]Any` enum
+pub trait SendAnyEvent {
+	/// The enum with one variant per event type in the set
+	type Any;
+
+	/// Sends an event to the event buffer of the type it holds
+	fn send_any(&mut self, event: Self::Any);
+}
+
+/// Allows an event set reader to read events of a given type
+pub trait ReadEvent<T> {
+	/// Iterates over the events that this reader hasn't seen yet
+	///
+	/// Calls [`EventReader.iter`](bevy::app::EventReader::iter()) with the Bevy event buffer of the corresponding type.
+	fn iter(&mut self) -> Box<dyn DoubleEndedIterator<Item = &T> + '_>;
+
+	/// Gets the most recent event that this reader hasn't seen yet
+	///
+	/// Calls [`EventReader.latest`](bevy::app::EventReader::latest()) with the Bevy event buffer of the corresponding type.
+	fn latest(&mut self) -> Option<&T>;
+}
+
+#[doc(hidden)]
+pub mod __private {
+	use crate::EventSetConfig;
+	use bevy::app::Events;
+	use bevy::ecs::{Component, Resources};
+	use std::any::TypeId;
+	use std::cell::UnsafeCell;
+	use std::collections::HashMap;
+	use std::sync::{Mutex, Once};
+
+	pub use crate::channel::EventSetChannel;
+	pub use crate::config::{add_event, mark_read, ReadMark};
+	pub use crate::delay::PendingEvents;
+	pub use crate::log::{log_event, LogEventSet, LogFilter};
+	pub use crate::order::{live, OrderIds, OrderReader, SendOrder, TypeReader};
+	pub use crate::probe::{ProbeEventSet, ProbeLog};
+	pub use crate::suppress::{is_suppressed, ReplaySuppression};
+	pub use bevy_event_set_macros::unique_events;
+	pub use paste::paste;
+	#[cfg(feature = "serde")]
+	pub use serde_crate as serde;
+
+	#[cfg(feature = "serde")]
+	pub use crate::record::{RecordEventSet, RecordWriter};
+	#[cfg(feature = "serde")]
+	pub use crate::replay::ReplayEventSet;
+
+	/// Gets the index of an enum variant for serialization
+	#[cfg(feature = "serde")]
+	pub fn variant_index(variants: &[&str], variant: &str) -> u32 {
+		variants.iter().position(|v| *v == variant).unwrap() as u32
+	}
+
+	/// Deserializes the name or index of an enum variant into its name
+	#[cfg(feature = "serde")]
+	#[derive(Clone, Copy)]
+	pub struct VariantSeed(pub &'static [&'static str]);
+
+	#[cfg(feature = "serde")]
+	impl<'de> serde::de::DeserializeSeed<'de> for VariantSeed {
+		type Value = &'static str;
+
+		fn deserialize<D: serde::Deserializer<'de>>(
+			self,
+			deserializer: D,
+		) -> Result<Self::Value, D::Error> {
+			deserializer.deserialize_identifier(self)
+		}
+	}
+
+	#[cfg(feature = "serde")]
+	impl<'de> serde::de::Visitor<'de> for VariantSeed {
+		type Value = &'static str;
+
+		fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+			write!(formatter, "one of the variants {:?}", self.0)
+		}
+
+		fn visit_u64<E: serde::de::Error>(self, index: u64) -> Result<Self::Value, E> {
+			self.0
+				.get(index as usize)
+				.copied()
+				.ok_or_else(|| E::invalid_value(serde::de::Unexpected::Unsigned(index), &self))
+		}
+
+		fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<Self::Value, E> {
+			self.0
+				.iter()
+				.find(|variant| **variant == name)
+				.copied()
+				.ok_or_else(|| E::unknown_variant(name, self.0))
+		}
+	}
+
+	/// Implemented by an event set for each of its event types
+	///
+	/// The macro can't compare types that are named in different ways. If one
+	/// is listed twice, this impl conflicts along with the other impls for the
+	/// type, and its error is the one that names the type in a readable way.
+	pub trait EventTypeMustBeUnique<T> {}
+
+	/// Gives access to the event buffer of one of the types of an event set
+	pub trait EventBuffer<T> {
+		fn buffer(&self) -> &Events<T>;
+		fn buffer_mut(&mut self) -> &mut Events<T>;
+	}
+
+	/// Gives access to the sent events of one of the types of a mock event set
+	pub trait MockBuffer<T> {
+		fn sent(&self) -> &Vec<T>;
+	}
+
+	/// Counts the events that are still in an event buffer
+	pub fn len<T: Component>(events: &Events<T>) -> usize {
+		events.get_reader().iter(events).count()
+	}
+
+	/// Lists that are built on first use and then kept for the rest of the program
+	///
+	/// Type names and ids can't be created in a constant, so the lists returned
+	/// by `type_names` and `type_ids` are stored in one of these. A static in a
+	/// generic function is shared by all its instances, so there is a list for
+	/// each instance of a generic event set, keyed by its sum enum.
+	pub struct TypeList<T: 'static> {
+		once: Once,
+		lists: UnsafeCell<Option<Lists<T>>>,
+	}
+
+	type Lists<T> = Mutex<HashMap<TypeId, &'static [T]>>;
+
+	// SAFETY: the cell is only written inside `Once::call_once`, which finishes before any
+	// thread reads it, and afterwards the map is behind a `Mutex`. The lists are shared
+	// between threads as `&'static [T]`, which is only safe if `T` is `Sync`.
+	unsafe impl<T: Sync + 'static> Sync for TypeList<T> {}
+
+	impl<T: 'static> Default for TypeList<T> {
+		fn default() -> Self {
+			TypeList::new()
+		}
+	}
+
+	impl<T: 'static> TypeList<T> {
+		pub const fn new() -> Self {
+			TypeList {
+				once: Once::new(),
+				lists: UnsafeCell::new(None),
+			}
+		}
+
+		pub fn get<K: 'static>(&'static self, init: impl FnOnce() -> Vec<T>) -> &'static [T] {
+			// SAFETY: `call_once` runs the write once, and blocks the other threads until it is
+			// done, so no thread reads the cell while it is written and it is never written again
+			let lists = unsafe {
+				self.once
+					.call_once(|| *self.lists.get() = Some(Mutex::new(HashMap::new())));
+				(*self.lists.get()).as_ref().unwrap()
+			};
+
+			let mut lists = lists.lock().unwrap();
+			let list = lists
+				.entry(TypeId::of::<K>())
+				.or_insert_with(|| Box::leak(init().into_boxed_slice()));
+			list
+		}
+	}
+
+	/// Uses the given stage if the config doesn't set one
+	pub fn default_stage(config: &EventSetConfig, stage: &'static str) -> EventSetConfig {
+		EventSetConfig {
+			stage: Some(config.stage.unwrap_or(stage)),
+			..config.clone()
+		}
+	}
+
+	/// Sends events of type `T` of an event set to the event buffers in the resources
+	pub trait SendToResources<T> {
+		fn send_batch<E: IntoIterator<Item = T>>(resources: &Resources, events: E);
+	}
+
+	/// Sends any event of an event set to the event buffers in the resources
+	pub trait SendAnyToResources {
+		type Any;
+
+		fn send_any(resources: &Resources, event: Self::Any);
+	}
+
+	/// Sends events of type `T` to the resources and records their order for the event set with sum enum `A`
+	pub fn send_to_resources<T: Component, A: 'static, E: IntoIterator<Item = T>>(
+		resources: &Resources,
+		events: E,
+	) {
+		if is_suppressed(resources.get::<ReplaySuppression<A>>().as_deref()) {
+			return;
+		}
+
+		let mut buffer = resources.get_mut::<Events<T>>().unwrap_or_else(|| {
+			panic!(
+				"no event buffer for `{}`, was the event set added to the app?",
+				std::any::type_name::<T>()
+			)
+		});
+
+		let mut count = 0;
+		for event in events {
+			buffer.send(event);
+			count += 1;
+		}
+
+		if count > 0 {
+			let ids = resources
+				.get::<OrderIds<A>>()
+				.expect("no send order ids, was the event set added to the app?");
+			resources
+				.get_mut::<Events<SendOrder<A>>>()
+				.expect("no send order buffer, was the event set added to the app?")
+				.send(ids.batch::<T>(count));
+		}
+	}
+}
+
+/// Implements the traits that need the `serde` feature for an event set
+///
+/// The `event_set!` macro expands in the crate that uses it, so it can't check
+/// the features of this crate itself. This macro is defined twice instead.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_set_serde {
+	([$cfg:meta] [[$($params:tt)*] [$($args:tt)*]] $name:ident $([$field:ident $variant:ident: $event:ty])*) => {
+		$crate::__private::paste! {
+			#[$cfg]
+			impl<'e, $($params)*> $crate::__private::serde::Serialize for [<$name Ref>]<'e, $($args)*>
+			where
+				$(&'e $event: $crate::__private::serde::Serialize,)*
+			{
+				fn serialize<__S: $crate::__private::serde::Serializer>(&self, serializer: __S) -> Result<__S::Ok, __S::Error> {
+					const VARIANTS: &[&str] = &[$(stringify!($variant)),*];
+
+					match self {
+						$(
+							[<$name Ref>]::$variant(event) => serializer.serialize_newtype_variant(
+								stringify!([<$name Any>]),
+								$crate::__private::variant_index(VARIANTS, stringify!($variant)),
+								stringify!($variant),
+								event,
+							),
+						)*
+					}
+				}
+			}
+
+			#[$cfg]
+			impl<'de, $($params)*> $crate::__private::serde::Deserialize<'de> for [<$name Any>]<$($args)*>
+			where
+				$($event: $crate::__private::serde::Deserialize<'de>,)*
+			{
+				fn deserialize<__D: $crate::__private::serde::Deserializer<'de>>(deserializer: __D) -> Result<Self, __D::Error> {
+					const VARIANTS: &[&str] = &[$(stringify!($variant)),*];
+
+					struct AnyVisitor<$($params)*>(std::marker::PhantomData<fn() -> [<$name Any>]<$($args)*>>);
+
+					impl<'de, $($params)*> $crate::__private::serde::de::Visitor<'de> for AnyVisitor<$($args)*>
+					where
+						$($event: $crate::__private::serde::Deserialize<'de>,)*
+					{
+						type Value = [<$name Any>]<$($args)*>;
+
+						fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+							formatter.write_str(concat!("enum ", stringify!([<$name Any>])))
+						}
+
+						fn visit_enum<__A: $crate::__private::serde::de::EnumAccess<'de>>(self, data: __A) -> Result<Self::Value, __A::Error> {
+							let (variant, access) = data.variant_seed($crate::__private::VariantSeed(VARIANTS))?;
+							match variant {
+								$(
+									stringify!($variant) => {
+										$crate::__private::serde::de::VariantAccess::newtype_variant::<$event>(access).map([<$name Any>]::$variant)
+									}
+								)*
+								_ => unreachable!(),
+							}
+						}
+					}
+
+					deserializer.deserialize_enum(stringify!([<$name Any>]), VARIANTS, AnyVisitor(std::marker::PhantomData))
+				}
+			}
+
+			#[$cfg]
+			impl<'a, $($params)*> $crate::__private::ReplayEventSet for $name<'a, $($args)*> {
+				fn add_replay_system(
+					app: &mut bevy::app::AppBuilder,
+					recording: Vec<$crate::Recorded<[<$name Any>]<$($args)*>>>,
+					suppress_live: bool,
+				) {
+					let mut recording = recording.into_iter().peekable();
+					let mut frame = 0;
+					let replay = move |mut events: $name<$($args)*>| {
+						// Live events are let through again from the first frame after the replay
+						if recording.peek().is_none() {
+							if let Some(suppression) = &events.suppression {
+								suppression.finish();
+							}
+						}
+
+						while matches!(recording.peek(), Some(recorded) if recorded.frame <= frame) {
+							match recording.next().unwrap().event {
+								$(
+									[<$name Any>]::$variant(event) => {
+										events.$field.send(event);
+										events.order.send(events.order_ids.of::<$event>());
+									}
+								)*
+							}
+						}
+						frame += 1;
+					};
+
+					if suppress_live {
+						app.add_resource($crate::__private::ReplaySuppression::<[<$name Any>]<$($args)*>>::default());
+					}
+					app.add_system_to_stage(bevy::app::stage::FIRST, bevy::ecs::IntoSystem::system(replay));
+				}
+			}
+
+			#[$cfg]
+			impl<'a, $($params)*> $crate::__private::RecordEventSet for $name<'a, $($args)*>
+			where
+				$(for<'x> &'x $event: $crate::__private::serde::Serialize,)*
+			{
+				fn add_record_system(app: &mut bevy::app::AppBuilder, mut writer: $crate::__private::RecordWriter) {
+					let record = move |mut reader: [<$name Reader>]<$($args)*>| {
+						for event in reader.read_all() {
+							writer.write(event);
+						}
+						writer.end_frame();
+					};
+
+					app.add_system_to_stage(bevy::app::stage::LAST, bevy::ecs::IntoSystem::system(record));
+				}
+			}
+		}
+	};
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_set_serde {
+	($($args:tt)*) => {};
 }
 
 /// Creates an event set
 ///
+/// Event types can be given as plain identifiers, as paths to types in other
+/// modules, or as generic instantiations:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// mod input {
+///     pub struct Jump;
+/// }
+///
+/// mod net {
+///     pub struct Packet<T>(pub T);
+///     pub struct Ping;
+/// }
+///
+/// event_set!(MyEvents { input::Jump, net::Packet<net::Ping> as Ping });
+///
+/// fn event_emitter_system(mut events: MyEvents) {
+///     events.send(input::Jump);
+///     events.send(net::Packet(net::Ping));
+/// }
+/// ```
+///
+/// An event set can contain up to 64 event types.
+///
+/// Attributes and doc comments before the name are added to the generated
+/// struct. Like other items, the event set is private to its module unless a
+/// visibility is given. The generated enums and traits name all event types
+/// and get the same visibility, so the event types must be at least as
+/// visible as the set. A `#[cfg]` attribute applies to everything the macro
+/// generates.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct EventOne;
+///
+/// event_set!(
+///     /// Events sent by the input systems
+///     pub(crate) MyEvents { EventOne }
+/// );
+/// ```
+///
+/// The macro also creates a `[name]Any` enum with one variant for each event
+/// type, which can be sent through the [`SendAnyEvent`] trait. Variants are
+/// named after the type (or the last segment of its path). Use `as` to give a
+/// variant a different name. Other types, such as generic ones, must be given
+/// a name with `as`:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct Jump;
+/// struct Packet<T>(T);
+/// struct Ping;
+///
+/// event_set!(MyEvents { Jump, Packet<Ping> as Ping });
+///
+/// fn event_emitter_system(mut events: MyEvents) {
+///     let actions: Vec<MyEventsAny> = vec![Jump.into(), MyEventsAny::Ping(Packet(Ping))];
+///     for action in actions {
+///         events.send_any(action);
+///     }
+/// }
+/// ```
+///
+/// An event set can include all events of other event sets with `..`. The
+/// types of a nested set are copied as they were written, so they must be
+/// nameable in the same way where the outer set is declared. Each event type is
+/// only registered once, even if it appears in several sets added to the app.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct Jump;
+/// struct Volume(f32);
+/// struct Score(usize);
+///
+/// event_set!(InputEvents { Jump });
+/// event_set!(AudioEvents { Volume });
+/// event_set!(GameEvents { ..InputEvents, ..AudioEvents, Score });
+///
+/// fn event_emitter_system(mut events: GameEvents) {
+///     events.send(Jump);
+///     events.send(Volume(0.5));
+///     events.send(Score(10));
+/// }
+/// ```
+///
+/// The sets share the event buffers, but each set records its own send order.
+/// Events sent through `InputEvents` can be read from `GameEvents`, but its
+/// `iter_all` treats them like events sent directly to their buffer: they take
+/// the place of the next event of the same type that was sent through
+/// `GameEvents`, or come last.
+///
+/// The members of a set are passed on by a macro that is only exported within
+/// its crate, so a set can't include a set from another crate.
+///
+/// Each event type can only be in a set once, also when it comes from a nested
+/// set. The macro compares the types as they are written, ignoring a leading
+/// `self::`, and names the duplicated type in its error:
+///
+/// ```compile_fail
+/// # use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct Jump;
+/// struct Score(usize);
+///
+/// event_set!(InputEvents { Jump });
+/// event_set!(GameEvents { ..InputEvents, Jump, Score });
+/// ```
+///
+/// The macro can't tell that two different paths, such as `Jump` and
+/// `crate::Jump`, name the same type. A type that is listed twice in different
+/// ways gives a conflicting implementation error (E0119) for every trait that
+/// the set implements for its event types. The error for
+/// `EventTypeMustBeUnique<T>` names the type.
+///
+/// A `[name]Sink` trait is implemented by everything that can send the events
+/// of the set. Code that takes it instead of the set can be tested with the
+/// `[name]Mock` struct, which keeps the events sent to it in memory.
+///
+/// The buffers of a set are updated in `stage::EVENT`, like regular Bevy
+/// events. A `#[stage(..)]` attribute picks another stage, unless the app
+/// overrides it with [`AddEventSet::add_event_set_in_stage`]:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct Collision;
+///
+/// event_set!(
+///     #[stage(stage::LAST)]
+///     PhysicsEvents { Collision }
+/// );
+/// ```
+///
 /// See the [crate-level documentation](./index.html) to see how to use this macro.
 #[macro_export]
 macro_rules! event_set {
-	($name:ident {}) => {
+	($(#[$($attr:tt)*])* $vis:vis $name:ident { $($events:tt)* }) => {
+		$crate::event_set!(@attributes [] [] [] $(#[$($attr)*])* $vis $name { $($events)* });
+	};
+
+	// Separates the `cfg` and `stage` attributes from the others
+	(@attributes [$($cfg:tt)*] $attrs:tt $stage:tt #[cfg($($predicate:tt)*)] $($rest:tt)*) => {
+		$crate::event_set!(@attributes [$($cfg)* ($($predicate)*)] $attrs $stage $($rest)*);
+	};
+	(@attributes $cfg:tt $attrs:tt [] #[stage($stage:expr)] $($rest:tt)*) => {
+		$crate::event_set!(@attributes $cfg $attrs [$stage] $($rest)*);
+	};
+	(@attributes $cfg:tt $attrs:tt [$($old:tt)+] #[stage($($stage:tt)*)] $($rest:tt)*) => {
+		compile_error!("an event set can only have one stage attribute");
+	};
+	(@attributes $cfg:tt [$($attr:tt)*] $stage:tt #[$($meta:tt)*] $($rest:tt)*) => {
+		$crate::event_set!(@attributes $cfg [$($attr)* #[$($meta)*]] $stage $($rest)*);
+	};
+	(@attributes [$(($($predicate:tt)*))*] $attrs:tt $stage:tt $vis:vis $name:ident { $($events:tt)* }) => {
+		$crate::event_set!(@item [cfg(all($($($predicate)*),*))] $attrs $stage [[] []] $vis $name { $($events)* });
+	};
+
+	// Entry point for the `events` attribute, all generated items get the `cfg`, the struct
+	// gets the other attributes and the buffers are updated in the `stage`, if there is one.
+	// The generics are the type parameters with their bounds and the type arguments, each
+	// followed by a comma. They are passed twice, once to take apart and once to pass on to
+	// the rules that are called for each event type. The type parameters of the generated
+	// methods and impls start with `__` so they don't clash with those of the set.
+	(@item [$cfg:meta] [$($attr:tt)*] [$($stage:expr)?] $generics:tt $vis:vis $name:ident {}) => {
 		compile_error!("cannot make an empty event set");
 	};
-	($name:ident { $($event:ident),* $(,)? }) => {
-		#[allow(non_snake_case)]
-		#[derive(bevy::ecs::SystemParam)]
-		pub struct $name<'a> {
+	(@item [$cfg:meta] [$($attr:tt)*] [$($stage:expr)?] $generics:tt $vis:vis $name:ident { $($events:tt)* }) => {
+		$crate::__private::unique_events!([$crate::event_set] [@expand ($) ([$cfg] [$($attr)*] [$($stage)?] $generics $generics $vis $name)] $($events)*);
+	};
+
+	// Called by `unique_events`, which names the fields and variants after it has added the
+	// members of the nested sets
+	(@expand ($d:tt) ([$cfg:meta] [$($attr:tt)*] [$($stage:expr)?] $generics:tt [[$($params:tt)*] [$($args:tt)*]] $vis:vis $name:ident) $([$field:ident $variant:ident: $event:ty])*) => {
+		$crate::__private::paste! {
+			$($attr)*
+			#[$cfg]
+			#[derive(bevy::ecs::SystemParam)]
+			$vis struct $name<'a, $($params)*> {
+				$(
+					$field: bevy::ecs::ResMut<'a, bevy::app::Events<$event>>,
+				)*
+				order: bevy::ecs::ResMut<'a, bevy::app::Events<$crate::__private::SendOrder<[<$name Any>]<$($args)*>>>>,
+				order_ids: bevy::ecs::Res<'a, $crate::__private::OrderIds<[<$name Any>]<$($args)*>>>,
+				pending: bevy::ecs::ResMut<'a, $crate::__private::PendingEvents<[<$name Any>]<$($args)*>>>,
+				suppression: Option<bevy::ecs::Res<'a, $crate::__private::ReplaySuppression<[<$name Any>]<$($args)*>>>>,
+			}
+
 			$(
-				$event: bevy::ecs::ResMut<'a, bevy::app::Events<$event>>,
+				$crate::event_set!(@event [$cfg] $generics $name [$field $variant: $event]);
 			)*
-		}
 
-		impl<'a> $crate::EventSet for $name<'a> {
-			fn apply(app: &mut bevy::app::AppBuilder) {
+			#[$cfg]
+			impl<'a, $($params)*> $crate::EventSet for $name<'a, $($args)*> {
+				fn apply_with(app: &mut bevy::app::AppBuilder, config: &$crate::EventSetConfig) {
+					$(
+						let config = &$crate::__private::default_stage(config, $stage);
+					)?
+					$(
+						$crate::__private::add_event::<$event>(app, config);
+					)*
+					$crate::__private::add_event::<$crate::__private::SendOrder<[<$name Any>]<$($args)*>>>(app, config);
+
+					if !app.resources().contains::<$crate::__private::EventSetChannel<[<$name Any>]<$($args)*>>>() {
+						let dispatch = |channel: bevy::ecs::Res<$crate::__private::EventSetChannel<[<$name Any>]<$($args)*>>>, mut events: $name<$($args)*>| {
+							for event in events.pending.release() {
+								$crate::SendAnyEvent::send_any(&mut events, event);
+							}
+							for event in channel.drain() {
+								$crate::SendAnyEvent::send_any(&mut events, event);
+							}
+						};
+
+						app.add_resource($crate::__private::EventSetChannel::<[<$name Any>]<$($args)*>>::default())
+							.add_resource($crate::__private::OrderIds::<[<$name Any>]<$($args)*>>::default())
+							.add_resource($crate::__private::PendingEvents::<[<$name Any>]<$($args)*>>::default())
+							.add_system_to_stage(bevy::app::stage::FIRST, bevy::ecs::IntoSystem::system(dispatch));
+					}
+				}
+			}
+
+			#[$cfg]
+			#[doc(hidden)]
+			#[allow(unused_macros)]
+			macro_rules! [<__ $name _members>] {
+				([$d($d callback:tt)*] [$d($d args:tt)*] [$d($d before:tt)*] [$d($d after:tt)*]) => {
+					$crate::__private::unique_events!([$d($d callback)*] [$d($d args)*] $d($d before)* $([$variant: $event],)* $d($d after)*);
+				};
+			}
+
+			#[$cfg]
+			#[doc(hidden)]
+			#[allow(unused_imports)]
+			pub(crate) use [<__ $name _members>];
+
+			// The companion items below are made for every set, so a set may leave them unused
+			#[doc = "Any event from the [`" $name "`] event set"]
+			#[$cfg]
+			#[allow(non_camel_case_types, dead_code)]
+			$vis enum [<$name Any>]<$($params)*> {
 				$(
-					app.add_event::<$event>();
+					#[doc = "A `" $variant "` event"]
+					$variant($event),
 				)*
 			}
-		}
 
-		$(
-			impl<'a> $crate::SendEvent<$event> for $name<'a> {
-				fn send(&mut self, event: $event) {
-					self.$event.send(event)
+			#[$cfg]
+			#[allow(dead_code)]
+			impl<'a, $($params)*> $name<'a, $($args)*> {
+				/// Sends an event at the start of the first frame after the delay has passed
+				pub fn send_delayed<__T: Into<[<$name Any>]<$($args)*>>>(&mut self, event: __T, delay: std::time::Duration) {
+					self.pending.after(event.into(), delay);
+				}
+
+				/// Sends an event at the start of a later frame, `1` being the next frame
+				pub fn send_after_frames<__T: Into<[<$name Any>]<$($args)*>>>(&mut self, event: __T, frames: u32) {
+					self.pending.after_frames(event.into(), frames);
+				}
+
+				/// Counts the events of the given type that are still in their event buffer
+				pub fn len_of<__T: bevy::ecs::Component>(&self) -> usize
+				where
+					Self: $crate::__private::EventBuffer<__T>,
+				{
+					$crate::__private::len($crate::__private::EventBuffer::<__T>::buffer(self))
+				}
+
+				/// Counts the events of all types that are still in their event buffers
+				pub fn total_len(&self) -> usize {
+					0 $(+ $crate::__private::len::<$event>(&self.$field))*
+				}
+
+				/// Returns `true` if the event buffers of all types are empty
+				pub fn is_empty(&self) -> bool {
+					self.total_len() == 0
+				}
+
+				/// Removes all events of the given type from their event buffer
+				pub fn clear<__T: bevy::ecs::Component>(&mut self)
+				where
+					Self: $crate::__private::EventBuffer<__T>,
+				{
+					$crate::__private::EventBuffer::<__T>::buffer_mut(self).clear();
+					self.order.send(self.order_ids.cleared::<__T>());
+				}
+
+				/// Removes all events from the event buffers of all types
+				///
+				/// Events scheduled with [`send_delayed`](Self::send_delayed()) or
+				/// [`send_after_frames`](Self::send_after_frames()) are still sent when they are due.
+				pub fn clear_all(&mut self) {
+					$(
+						self.$field.clear();
+					)*
+					self.order.clear();
+				}
+
+				/// Removes all events from the event buffers of all types and returns them in the order they were sent
+				///
+				/// The order is only recorded for events sent through the event set. Events sent directly to an
+				/// event buffer take the place of the next event of the same type, or come last if there is none.
+				pub fn drain_all(&mut self) -> std::vec::IntoIter<[<$name Any>]<$($args)*>> {
+					$(
+						let mut $field = self.$field.drain();
+					)*
+
+					let order: Vec<_> = self.order.drain().collect();
+					let mut events = Vec::new();
+					for order in $crate::__private::live(&order) {
+						$(
+							if order.is::<$event>() {
+								events.extend($field.by_ref().take(order.count()).map([<$name Any>]::$variant));
+								continue;
+							}
+						)*
+					}
+					$(
+						events.extend($field.map([<$name Any>]::$variant));
+					)*
+					events.into_iter()
+				}
+
+				#[doc = "The names of the event types in this event set, in the order of the variants of [`" $name "Any`]"]
+				pub fn type_names() -> &'static [&'static str] {
+					static NAMES: $crate::__private::TypeList<&'static str> = $crate::__private::TypeList::new();
+					NAMES.get::<[<$name Any>]<$($args)*>>(|| vec![$(std::any::type_name::<$event>()),*])
+				}
+
+				#[doc = "The ids of the event types in this event set, in the order of the variants of [`" $name "Any`]"]
+				pub fn type_ids() -> &'static [std::any::TypeId] {
+					static IDS: $crate::__private::TypeList<std::any::TypeId> = $crate::__private::TypeList::new();
+					IDS.get::<[<$name Any>]<$($args)*>>(|| vec![$(std::any::TypeId::of::<$event>()),*])
 				}
 			}
-		)*
-	};
-}
 
-#[cfg(test)]
-mod tests {
-	// These tests just check if the macros compile
+			#[$cfg]
+			impl<'a, $($params)*> $crate::SendAnyEvent for $name<'a, $($args)*> {
+				type Any = [<$name Any>]<$($args)*>;
 
-	use super::*;
+				fn send_any(&mut self, event: [<$name Any>]<$($args)*>) {
+					match event {
+						$(
+							[<$name Any>]::$variant(event) => $crate::SendEvent::<$event>::send(self, event),
+						)*
+					}
+				}
+			}
 
-	#[test]
-	fn single() {
-		struct TestEvent;
-		event_set!(MyEvents { TestEvent });
-		event_set!(MyEvents2 { TestEvent });
-	}
+			#[$cfg]
+			impl<'a, $($params)*> $crate::__private::SendAnyToResources for $name<'a, $($args)*> {
+				type Any = [<$name Any>]<$($args)*>;
 
-	#[test]
-	fn multiple() {
-		struct TestEvent1(usize);
-		struct TestEvent2 {
-			number: usize,
-		}
+				fn send_any(resources: &bevy::ecs::Resources, event: [<$name Any>]<$($args)*>) {
+					match event {
+						$(
+							[<$name Any>]::$variant(event) => {
+								<Self as $crate::__private::SendToResources<$event>>::send_batch(resources, Some(event))
+							}
+						)*
+					}
+				}
+			}
 
-		event_set!(MyEvents {
-			TestEvent1,
-			TestEvent2
-		});
-	}
+			#[doc = "Reads events from the [`" $name "`] event set"]
+			#[$cfg]
+			#[derive(bevy::ecs::SystemParam)]
+			#[allow(dead_code)]
+			$vis struct [<$name Reader>]<'a, $($params)*> {
+				$(
+					$field: bevy::ecs::Local<'a, $crate::__private::TypeReader<$event>>,
+					[<$field _events>]: bevy::ecs::Res<'a, bevy::app::Events<$event>>,
+					[<$field _read>]: Option<bevy::ecs::Res<'a, $crate::__private::ReadMark<$event>>>,
+				)*
+				order: bevy::ecs::Local<'a, $crate::__private::OrderReader<[<$name Any>]<$($args)*>>>,
+				order_ids: bevy::ecs::Res<'a, $crate::__private::OrderIds<[<$name Any>]<$($args)*>>>,
+				order_events: bevy::ecs::Res<'a, bevy::app::Events<$crate::__private::SendOrder<[<$name Any>]<$($args)*>>>>,
+				order_read: Option<bevy::ecs::Res<'a, $crate::__private::ReadMark<$crate::__private::SendOrder<[<$name Any>]<$($args)*>>>>>,
+			}
 
-	#[test]
-	fn add_to_builder() {
-		use bevy::app::App;
+			#[doc = "A reference to any event from the [`" $name "`] event set"]
+			#[$cfg]
+			#[allow(non_camel_case_types, dead_code)]
+			$vis enum [<$name Ref>]<'e, $($params)*> {
+				$(
+					#[doc = "A `" $variant "` event"]
+					$variant(&'e $event),
+				)*
+			}
 
-		struct TestEvent;
-		event_set!(MyEvents { TestEvent });
+			#[$cfg]
+			#[allow(dead_code)]
+			impl<'a, $($params)*> [<$name Reader>]<'a, $($args)*> {
+				/// Iterates over the events of the given type that this reader hasn't seen yet
+				pub fn iter<__T>(&mut self) -> Box<dyn DoubleEndedIterator<Item = &__T> + '_>
+				where
+					Self: $crate::ReadEvent<__T>,
+				{
+					$crate::ReadEvent::<__T>::iter(self)
+				}
 
-		App::build().add_event_set::<MyEvents>();
+				/// Gets the most recent event of the given type that this reader hasn't seen yet
+				pub fn latest<__T>(&mut self) -> Option<&__T>
+				where
+					Self: $crate::ReadEvent<__T>,
+				{
+					$crate::ReadEvent::<__T>::latest(self)
+				}
+
+				/// Iterates over the events of all types that this reader hasn't seen yet, in the order they were sent
+				///
+				/// The order is only recorded for events sent through the event set. Events sent directly to an
+				/// event buffer take the place of the next event of the same type, or come last if there is none.
+				/// Events that were already read through [`iter`](Self::iter()) or [`latest`](Self::latest()) are
+				/// left out, without changing the order of the others.
+				pub fn iter_all(&mut self) -> std::vec::IntoIter<[<$name Ref>]<'_, $($args)*>> {
+					$(
+						$crate::__private::mark_read(self.[<$field _read>].as_deref());
+					)*
+					$crate::__private::mark_read(self.order_read.as_deref());
+
+					self.read_all()
+				}
+
+				/// Collects the unseen events of all types in the order they were sent, without marking them as read
+				fn read_all(&mut self) -> std::vec::IntoIter<[<$name Ref>]<'_, $($args)*>> {
+					$(
+						let mut $field = self.$field.iter(&self.[<$field _events>]);
+					)*
+
+					let order = self.order.unread(&self.order_events);
+					self.order.mark(&order);
+
+					let mut events = Vec::new();
+					for order in order {
+						$(
+							if order.is::<$event>() {
+								let unseen = self.$field.unseen(order);
+								events.extend($field.by_ref().take(unseen).map([<$name Ref>]::$variant));
+								continue;
+							}
+						)*
+					}
+					$(
+						events.extend($field.map([<$name Ref>]::$variant));
+					)*
+					events.into_iter()
+				}
+			}
+
+			#[$cfg]
+			impl<'a, $($params)*> $crate::__private::LogEventSet for $name<'a, $($args)*>
+			where
+				$(for<'x> &'x $event: std::fmt::Debug,)*
+			{
+				fn add_log_system(app: &mut bevy::app::AppBuilder, filter: $crate::__private::LogFilter) {
+					let log = move |mut reader: [<$name Reader>]<$($args)*>| {
+						for event in reader.read_all() {
+							match event {
+								$(
+									[<$name Ref>]::$variant(event) => $crate::__private::log_event::<$event>(&filter, &event),
+								)*
+							}
+						}
+					};
+
+					app.add_system_to_stage(bevy::app::stage::LAST, bevy::ecs::IntoSystem::system(log));
+				}
+			}
+
+			#[doc = "Sends events of all types of the [`" $name "`] event set"]
+			///
+			/// Implemented by everything that can send the events of the set, such
+			/// as the set itself, its mock and the sets borrowed from resources or
+			/// commands. Taking this instead of the set lets the sending code be
+			/// tested with the mock.
+			#[$cfg]
+			#[allow(dead_code)]
+			$vis trait [<$name Sink>]<$($params)*>: $($crate::SendEvent<$event> +)* $crate::SendAnyEvent<Any = [<$name Any>]<$($args)*>> {}
+
+			#[$cfg]
+			impl<__S: $($crate::SendEvent<$event> +)* $crate::SendAnyEvent<Any = [<$name Any>]<$($args)*>>, $($params)*> [<$name Sink>]<$($args)*> for __S {}
+
+			#[doc = "Keeps the events sent to it in memory, to test code that sends events of the [`" $name "`] event set without an app"]
+			#[$cfg]
+			#[allow(dead_code)]
+			$vis struct [<$name Mock>]<$($params)*> {
+				$(
+					$field: Vec<$event>,
+				)*
+				order: Vec<$crate::__private::SendOrder<[<$name Any>]<$($args)*>>>,
+				order_ids: $crate::__private::OrderIds<[<$name Any>]<$($args)*>>,
+			}
+
+			// Deriving `Default` would require the type parameters to implement it
+			#[$cfg]
+			impl<$($params)*> Default for [<$name Mock>]<$($args)*> {
+				fn default() -> Self {
+					[<$name Mock>] {
+						$(
+							$field: Vec::new(),
+						)*
+						order: Vec::new(),
+						order_ids: Default::default(),
+					}
+				}
+			}
+
+			#[$cfg]
+			impl<$($params)*> $crate::SendAnyEvent for [<$name Mock>]<$($args)*> {
+				type Any = [<$name Any>]<$($args)*>;
+
+				fn send_any(&mut self, event: [<$name Any>]<$($args)*>) {
+					match event {
+						$(
+							[<$name Any>]::$variant(event) => $crate::SendEvent::<$event>::send(self, event),
+						)*
+					}
+				}
+			}
+
+			#[$cfg]
+			#[allow(dead_code)]
+			impl<$($params)*> [<$name Mock>]<$($args)*> {
+				/// Gets the events of the given type that were sent, in the order they were sent
+				pub fn sent<__T>(&self) -> &[__T]
+				where
+					Self: $crate::__private::MockBuffer<__T>,
+				{
+					$crate::__private::MockBuffer::<__T>::sent(self)
+				}
+
+				/// Counts the events of all types that were sent
+				pub fn total_len(&self) -> usize {
+					0 $(+ self.$field.len())*
+				}
+
+				/// Returns `true` if no events were sent
+				pub fn is_empty(&self) -> bool {
+					self.total_len() == 0
+				}
+
+				/// Removes all sent events
+				pub fn clear_all(&mut self) {
+					$(
+						self.$field.clear();
+					)*
+					self.order.clear();
+				}
+
+				/// Removes all sent events and returns them in the order they were sent
+				pub fn drain_all(&mut self) -> std::vec::IntoIter<[<$name Any>]<$($args)*>> {
+					$(
+						let mut $field = self.$field.drain(..);
+					)*
+
+					let mut events = Vec::new();
+					for order in self.order.drain(..) {
+						$(
+							if order.is::<$event>() {
+								events.extend($field.by_ref().take(order.count()).map([<$name Any>]::$variant));
+								continue;
+							}
+						)*
+					}
+					events.into_iter()
+				}
+			}
+
+			#[$cfg]
+			impl<'a, $($params)*> $crate::__private::ProbeEventSet for $name<'a, $($args)*>
+			where
+				// The unused `'x` works around the error for trivially false bounds on concrete types that aren't `Clone`
+				$(for<'x> $event: Clone,)*
+			{
+				fn add_probe_system(app: &mut bevy::app::AppBuilder, events: $crate::__private::ProbeLog) {
+					let probe = move |mut reader: [<$name Reader>]<$($args)*>| {
+						for event in reader.read_all() {
+							match event {
+								$(
+									[<$name Ref>]::$variant(event) => events.push::<$event>(event.clone()),
+								)*
+							}
+						}
+					};
+
+					app.add_system_to_stage(bevy::app::stage::LAST, bevy::ecs::IntoSystem::system(probe));
+				}
+			}
+
+			$crate::__event_set_serde!([$cfg] $generics $name $([$field $variant: $event])*);
+		}
+	};
+
+	// Implements the traits of an event set that take one of its event types
+	(@event [$cfg:meta] [[$($params:tt)*] [$($args:tt)*]] $name:ident [$field:ident $variant:ident: $event:ty]) => {
+		$crate::__private::paste! {
+			#[$cfg]
+			impl<'a, $($params)*> $crate::__private::EventTypeMustBeUnique<$event> for $name<'a, $($args)*> {}
+
+			#[$cfg]
+			impl<'a, $($params)*> $crate::__private::EventBuffer<$event> for $name<'a, $($args)*> {
+				fn buffer(&self) -> &bevy::app::Events<$event> {
+					&self.$field
+				}
+
+				fn buffer_mut(&mut self) -> &mut bevy::app::Events<$event> {
+					&mut self.$field
+				}
+			}
+
+			#[$cfg]
+			impl<'a, $($params)*> $crate::SendEvent<$event> for $name<'a, $($args)*> {
+				fn send(&mut self, event: $event) {
+					if $crate::__private::is_suppressed(self.suppression.as_deref()) {
+						return;
+					}
+
+					self.$field.send(event);
+					self.order.send(self.order_ids.of::<$event>());
+				}
+
+				fn send_batch<__E: IntoIterator<Item = $event>>(&mut self, events: __E) {
+					if $crate::__private::is_suppressed(self.suppression.as_deref()) {
+						return;
+					}
+
+					let mut count = 0;
+					for event in events {
+						self.$field.send(event);
+						count += 1;
+					}
+
+					if count > 0 {
+						self.order.send(self.order_ids.batch::<$event>(count));
+					}
+				}
+			}
+
+			#[$cfg]
+			impl<'a, $($params)*> std::iter::Extend<$event> for $name<'a, $($args)*> {
+				fn extend<__E: IntoIterator<Item = $event>>(&mut self, events: __E) {
+					$crate::SendEvent::<$event>::send_batch(self, events);
+				}
+			}
+
+			#[$cfg]
+			impl<$($params)*> From<$event> for [<$name Any>]<$($args)*> {
+				fn from(event: $event) -> Self {
+					[<$name Any>]::$variant(event)
+				}
+			}
+
+			#[$cfg]
+			impl<'a, $($params)*> $crate::__private::SendToResources<$event> for $name<'a, $($args)*> {
+				fn send_batch<__E: IntoIterator<Item = $event>>(resources: &bevy::ecs::Resources, events: __E) {
+					$crate::__private::send_to_resources::<$event, [<$name Any>]<$($args)*>, __E>(resources, events);
+				}
+			}
+
+			#[$cfg]
+			impl<'a, $($params)*> $crate::ReadEvent<$event> for [<$name Reader>]<'a, $($args)*> {
+				fn iter(&mut self) -> Box<dyn DoubleEndedIterator<Item = &$event> + '_> {
+					$crate::__private::mark_read(self.[<$field _read>].as_deref());
+					$crate::__private::mark_read(self.order_read.as_deref());
+					Box::new(self.$field.read(&self.[<$field _events>], &self.order_ids))
+				}
+
+				fn latest(&mut self) -> Option<&$event> {
+					$crate::__private::mark_read(self.[<$field _read>].as_deref());
+					$crate::__private::mark_read(self.order_read.as_deref());
+					self.$field.read(&self.[<$field _events>], &self.order_ids).next_back()
+				}
+			}
+
+			#[$cfg]
+			impl<$($params)*> $crate::__private::MockBuffer<$event> for [<$name Mock>]<$($args)*> {
+				fn sent(&self) -> &Vec<$event> {
+					&self.$field
+				}
+			}
+
+			#[$cfg]
+			impl<$($params)*> $crate::SendEvent<$event> for [<$name Mock>]<$($args)*> {
+				fn send(&mut self, event: $event) {
+					self.$field.push(event);
+					self.order.push(self.order_ids.of::<$event>());
+				}
+
+				fn send_batch<__E: IntoIterator<Item = $event>>(&mut self, events: __E) {
+					let count = self.$field.len();
+					self.$field.extend(events);
+
+					let count = self.$field.len() - count;
+					if count > 0 {
+						self.order.push(self.order_ids.batch::<$event>(count));
+					}
+				}
+			}
+
+			#[$cfg]
+			impl<$($params)*> std::iter::Extend<$event> for [<$name Mock>]<$($args)*> {
+				fn extend<__E: IntoIterator<Item = $event>>(&mut self, events: __E) {
+					$crate::SendEvent::<$event>::send_batch(self, events);
+				}
+			}
+		}
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	// Most tests run an app and check the events that the systems read or sent,
+	// the tests without assertions only check that the macros compile
+	use super::*;
+
+	struct TestEvent1(usize);
+	struct TestEvent2(usize);
+	struct TestEvent3(usize);
+	event_set!(TestEvents {
+		TestEvent1,
+		TestEvent2,
+		TestEvent3
+	});
+
+	/// The values of the `TestEvents` events that were read, in the order they were sent
+	#[derive(Default)]
+	struct Received(Vec<usize>);
+
+	/// Makes an app with the `TestEvents` set that reads its events into `Received` in `stage::UPDATE`
+	fn test_app() -> AppBuilder {
+		use bevy::app::{stage, App};
+		use bevy::ecs::{IntoSystem, ResMut};
+
+		fn receive(mut events: TestEventsReader, mut received: ResMut<Received>) {
+			received
+				.0
+				.extend(events.iter_all().map(|event| match event {
+					TestEventsRef::TestEvent1(e) => e.0,
+					TestEventsRef::TestEvent2(e) => e.0,
+					TestEventsRef::TestEvent3(e) => e.0,
+				}));
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<TestEvents>()
+			.add_resource(Received::default())
+			.add_system_to_stage(stage::UPDATE, receive.system());
+		app
+	}
+
+	fn received(app: &AppBuilder) -> Vec<usize> {
+		app.resources().get::<Received>().unwrap().0.clone()
+	}
+
+	#[test]
+	fn single() {
+		struct TestEvent;
+		event_set!(MyEvents { TestEvent });
+		event_set!(MyEvents2 { TestEvent });
+	}
+
+	#[test]
+	fn multiple() {
+		#[allow(dead_code)]
+		struct TestEvent1(usize);
+		#[allow(dead_code)]
+		struct TestEvent2 {
+			number: usize,
+		}
+
+		event_set!(MyEvents {
+			TestEvent1,
+			TestEvent2
+		});
+	}
+
+	#[test]
+	fn add_to_builder() {
+		use bevy::app::App;
+
+		struct TestEvent;
+		event_set!(MyEvents { TestEvent });
+
+		App::build().add_event_set::<MyEvents>();
+	}
+
+	#[test]
+	fn reader() {
+		use bevy::app::{stage, App};
+		use bevy::ecs::{IntoSystem, ResMut};
+
+		struct TestEvent1(usize);
+		struct TestEvent2(usize);
+		event_set!(MyEvents {
+			TestEvent1,
+			TestEvent2
+		});
+
+		#[derive(Default)]
+		struct Received(Vec<usize>);
+
+		fn emit(mut events: MyEvents) {
+			events.send(TestEvent1(1));
+			events.send(TestEvent2(2));
+			events.send(TestEvent1(3));
+		}
+
+		fn receive(mut events: MyEventsReader, mut received: ResMut<Received>) {
+			received.0.extend(events.iter::<TestEvent1>().map(|e| e.0));
+			received
+				.0
+				.extend(events.latest::<TestEvent2>().map(|e| e.0));
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<MyEvents>()
+			.add_resource(Received::default())
+			.add_system_to_stage(stage::PRE_UPDATE, emit.system())
+			.add_system_to_stage(stage::UPDATE, receive.system());
+		app.app.update();
+
+		let received = app.resources().get::<Received>().unwrap();
+		assert_eq!(received.0, vec![1, 3, 2]);
+	}
+
+	#[test]
+	fn send_any() {
+		use bevy::app::{stage, App};
+		use bevy::ecs::{IntoSystem, ResMut};
+
+		struct Packet<T>(T);
+		struct Ping(usize);
+		struct Pong(usize);
+
+		mod input {
+			pub struct Jump(pub usize);
+		}
+
+		event_set!(MyEvents {
+			input::Jump,
+			Packet<Ping> as Ping,
+			Packet<Pong> as Pong,
+		});
+
+		#[derive(Default)]
+		struct Received(Vec<usize>);
+
+		fn emit(mut events: MyEvents) {
+			let events_to_send: Vec<MyEventsAny> = vec![
+				input::Jump(1).into(),
+				Packet(Ping(2)).into(),
+				MyEventsAny::Pong(Packet(Pong(3))),
+				MyEventsAny::Jump(input::Jump(4)),
+			];
+
+			for event in events_to_send {
+				events.send_any(event);
+			}
+		}
+
+		fn receive(mut events: MyEventsReader, mut received: ResMut<Received>) {
+			received.0.extend(events.iter::<input::Jump>().map(|e| e.0));
+			received
+				.0
+				.extend(events.iter::<Packet<Ping>>().map(|e| (e.0).0));
+			received
+				.0
+				.extend(events.iter::<Packet<Pong>>().map(|e| (e.0).0));
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<MyEvents>()
+			.add_resource(Received::default())
+			.add_system_to_stage(stage::PRE_UPDATE, emit.system())
+			.add_system_to_stage(stage::UPDATE, receive.system());
+		app.app.update();
+
+		let received = app.resources().get::<Received>().unwrap();
+		assert_eq!(received.0, vec![1, 4, 2, 3]);
+	}
+
+	#[test]
+	fn tuple() {
+		use bevy::app::{stage, App, Events};
+		use bevy::ecs::{IntoSystem, Res, ResMut};
+
+		struct Jump(usize);
+		struct Crouch(usize);
+
+		#[derive(Default)]
+		struct Received(Vec<usize>);
+
+		fn send_jump<S: SendEvent<Jump, I>, I>(events: &mut S) {
+			events.send(Jump(1));
+		}
+
+		fn emit(mut events: EventSetOf<(Jump, Crouch)>) {
+			send_jump(&mut events);
+			events.send(Crouch(2));
+		}
+
+		fn receive(
+			jumps: Res<Events<Jump>>,
+			crouches: Res<Events<Crouch>>,
+			mut received: ResMut<Received>,
+		) {
+			received
+				.0
+				.extend(jumps.get_reader().iter(&jumps).map(|e| e.0));
+			received
+				.0
+				.extend(crouches.get_reader().iter(&crouches).map(|e| e.0));
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<EventSetOf<(Jump, Crouch)>>()
+			.add_resource(Received::default())
+			.add_system_to_stage(stage::PRE_UPDATE, emit.system())
+			.add_system_to_stage(stage::UPDATE, receive.system());
+		app.app.update();
+
+		let received = app.resources().get::<Received>().unwrap();
+		assert_eq!(received.0, vec![1, 2]);
+	}
+
+	#[test]
+	fn iter_all() {
+		use bevy::app::{stage, Events};
+		use bevy::ecs::{IntoSystem, ResMut};
+
+		fn emit(mut events: TestEvents, mut direct: ResMut<Events<TestEvent2>>) {
+			events.send(TestEvent1(1));
+			events.send(TestEvent2(2));
+			events.send(TestEvent2(3));
+			events.send(TestEvent1(4));
+			direct.send(TestEvent2(5));
+		}
+
+		let mut app = test_app();
+		app.add_system_to_stage(stage::PRE_UPDATE, emit.system());
+		app.app.update();
+
+		assert_eq!(received(&app), vec![1, 2, 3, 4, 5]);
+	}
+
+	#[test]
+	fn iter_all_after_iter() {
+		use bevy::app::{stage, App};
+		use bevy::ecs::{IntoSystem, Local, ResMut};
+
+		fn emit(mut events: TestEvents, mut frame: Local<usize>) {
+			events.send(TestEvent1(*frame * 10 + 1));
+			events.send(TestEvent2(*frame * 10 + 2));
+			*frame += 1;
+		}
+
+		// Both reads have to use the same reader, so this doesn't use the receive system of `test_app`
+		fn receive(mut events: TestEventsReader, mut received: ResMut<Received>) {
+			if received.0.is_empty() {
+				received.0.extend(events.iter::<TestEvent1>().map(|e| e.0));
+				return;
+			}
+
+			received
+				.0
+				.extend(events.iter_all().map(|event| match event {
+					TestEventsRef::TestEvent1(e) => e.0,
+					TestEventsRef::TestEvent2(e) => e.0,
+					TestEventsRef::TestEvent3(e) => e.0,
+				}));
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<TestEvents>()
+			.add_resource(Received::default())
+			.add_system_to_stage(stage::PRE_UPDATE, emit.system())
+			.add_system_to_stage(stage::UPDATE, receive.system());
+		app.app.update();
+		app.app.update();
+
+		assert_eq!(received(&app), vec![1, 2, 11, 12]);
+	}
+
+	#[test]
+	fn batch() {
+		use bevy::app::stage;
+		use bevy::ecs::IntoSystem;
+
+		fn emit(mut events: TestEvents) {
+			events.send(TestEvent1(1));
+			events.send_batch(vec![TestEvent2(2), TestEvent2(3)]);
+			events.extend((4..6).map(TestEvent1));
+			events.send_batch(Vec::<TestEvent2>::new());
+			events.send(TestEvent2(6));
+		}
+
+		let mut app = test_app();
+		app.add_system_to_stage(stage::PRE_UPDATE, emit.system());
+		app.app.update();
+
+		assert_eq!(received(&app), vec![1, 2, 3, 4, 5, 6]);
+	}
+
+	#[test]
+	fn resources() {
+		use bevy::app::stage;
+		use bevy::ecs::{IntoSystem, Resources, World};
+
+		fn emit(_world: &mut World, resources: &mut Resources) {
+			let mut events = resources.event_set::<TestEvents>();
+			events.send(TestEvent2(2));
+			events.send_any(TestEventsAny::TestEvent1(TestEvent1(3)));
+		}
+
+		let mut app = test_app();
+		app.add_system_to_stage(stage::PRE_UPDATE, emit.system());
+
+		app.resources()
+			.event_set::<TestEvents>()
+			.extend(vec![TestEvent1(0), TestEvent1(1)]);
+		app.app.update();
+
+		assert_eq!(received(&app), vec![0, 1, 2, 3]);
+	}
+
+	#[test]
+	fn commands() {
+		use bevy::app::{stage, Events};
+		use bevy::ecs::{Commands, IntoSystem};
+
+		struct Other(usize);
+
+		fn emit(commands: &mut Commands) {
+			commands.send_event(Other(0));
+
+			let mut events = commands.event_set::<TestEvents>();
+			events.send(TestEvent2(1));
+			events.send_batch(vec![TestEvent1(2), TestEvent1(3)]);
+			events.send_any(TestEventsAny::TestEvent2(TestEvent2(4)));
+		}
+
+		let mut app = test_app();
+		app.add_event::<Other>()
+			.add_system_to_stage(stage::PRE_UPDATE, emit.system());
+		app.app.update();
+
+		assert_eq!(received(&app), vec![1, 2, 3, 4]);
+
+		let other = app.resources().get::<Events<Other>>().unwrap();
+		let other: Vec<_> = other.get_reader().iter(&other).map(|e| e.0).collect();
+		assert_eq!(other, vec![0]);
+	}
+
+	#[test]
+	fn only() {
+		use bevy::app::stage;
+		use bevy::ecs::IntoSystem;
+
+		fn emit_one(mut events: Only<TestEvents, (TestEvent1,), Ordered>) {
+			events.send(TestEvent1(1));
+		}
+
+		fn emit_others(mut events: Only<TestEvents, (TestEvent3, TestEvent2), Ordered>) {
+			events.send(TestEvent2(2));
+			events.send_batch(vec![TestEvent3(3), TestEvent3(4)]);
+		}
+
+		fn emit_unordered(mut events: Only<TestEvents, (TestEvent2,)>) {
+			events.send(TestEvent2(5));
+		}
+
+		let mut app = test_app();
+		app.add_system_to_stage(stage::PRE_UPDATE, emit_one.system())
+			.add_system_to_stage(stage::PRE_UPDATE, emit_others.system())
+			.add_system_to_stage(stage::PRE_UPDATE, emit_unordered.system());
+		app.app.update();
+
+		assert_eq!(received(&app), vec![1, 2, 3, 4, 5]);
+	}
+
+	#[test]
+	fn channel() {
+		let mut app = test_app();
+		app.add_event_set::<TestEvents>();
+
+		let sender = app.resources().event_set_sender::<TestEvents>();
+		let thread_sender = sender.clone();
+		std::thread::spawn(move || {
+			thread_sender.send(TestEvent2(1)).unwrap();
+			thread_sender.send(TestEvent1(2)).unwrap();
+		})
+		.join()
+		.unwrap();
+		sender.send(TestEvent2(3)).unwrap();
+
+		app.app.update();
+
+		assert_eq!(received(&app), vec![1, 2, 3]);
+	}
+
+	#[test]
+	fn delayed() {
+		use bevy::app::stage;
+		use bevy::ecs::{IntoSystem, Local};
+		use std::time::Duration;
+
+		fn emit(mut events: TestEvents, mut sent: Local<bool>) {
+			if !*sent {
+				events.send_after_frames(TestEvent1(1), 2);
+				events.send_delayed(TestEvent2(2), Duration::from_secs(0));
+				events.send_delayed(TestEvent2(3), Duration::from_secs(3600));
+				*sent = true;
+			}
+		}
+
+		let mut app = test_app();
+		app.add_system_to_stage(stage::PRE_UPDATE, emit.system());
+
+		app.app.update();
+		assert!(received(&app).is_empty());
+
+		app.app.update();
+		assert_eq!(received(&app), vec![2]);
+
+		app.app.update();
+		assert_eq!(received(&app), vec![2, 1]);
+	}
+
+	#[test]
+	fn retention() {
+		use bevy::app::{stage, App, AppBuilder, Events};
+		use bevy::ecs::{IntoSystem, Res};
+
+		struct TestEvent;
+		event_set!(MyEvents { TestEvent });
+
+		struct ReadNow(bool);
+
+		fn receive(mut events: MyEventsReader, read_now: Res<ReadNow>) {
+			if read_now.0 {
+				events.iter::<TestEvent>().count();
+			}
+		}
+
+		fn app_with(retention: Retention) -> AppBuilder {
+			let mut app = App::build();
+			app.add_event_set_with::<MyEvents>(EventSetConfig {
+				retention,
+				..Default::default()
+			})
+			.add_resource(ReadNow(false))
+			.add_system_to_stage(stage::UPDATE, receive.system());
+			app.resources().event_set::<MyEvents>().send(TestEvent);
+			app
+		}
+
+		fn count_after_updates(app: &mut AppBuilder, updates: usize) -> usize {
+			for _ in 0..updates {
+				app.app.update();
+			}
+			let events = app.resources().get::<Events<TestEvent>>().unwrap();
+			events.get_reader().iter(&events).count()
+		}
+
+		let mut app = app_with(Retention::Frames(1));
+		assert_eq!(count_after_updates(&mut app, 1), 1);
+		assert_eq!(count_after_updates(&mut app, 1), 0);
+
+		let mut app = app_with(Retention::Frames(2));
+		assert_eq!(count_after_updates(&mut app, 3), 1);
+		assert_eq!(count_after_updates(&mut app, 1), 0);
+
+		let mut app = app_with(Retention::Manual);
+		assert_eq!(count_after_updates(&mut app, 10), 1);
+
+		let mut app = app_with(Retention::UntilRead);
+		assert_eq!(count_after_updates(&mut app, 10), 1);
+		app.resources_mut().insert(ReadNow(true));
+		assert_eq!(count_after_updates(&mut app, 2), 1);
+		assert_eq!(count_after_updates(&mut app, 1), 0);
+	}
+
+	#[test]
+	#[should_panic(expected = "events can't be kept for 0 frames")]
+	fn retention_zero() {
+		use bevy::app::App;
+
+		App::build().add_event_set_with::<TestEvents>(EventSetConfig {
+			retention: Retention::Frames(0),
+			..Default::default()
+		});
+	}
+
+	#[test]
+	fn lock_step() {
+		use bevy::app::{stage, App, Events};
+		use bevy::ecs::{IntoSystem, Res, ResMut, ShouldRun, SystemStage};
+
+		struct TestEvent;
+		event_set!(MyEvents { TestEvent });
+
+		struct Step(bool);
+
+		#[derive(Default)]
+		struct Received(usize);
+
+		fn step(step: Res<Step>) -> ShouldRun {
+			if step.0 {
+				ShouldRun::Yes
+			} else {
+				ShouldRun::No
+			}
+		}
+
+		fn receive(mut events: MyEventsReader, mut received: ResMut<Received>) {
+			received.0 += events.iter::<TestEvent>().count();
+		}
+
+		fn count(app: &App) -> usize {
+			let events = app.resources.get::<Events<TestEvent>>().unwrap();
+			events.get_reader().iter(&events).count()
+		}
+
+		let mut app = App::build();
+		app.add_stage_after(
+			stage::UPDATE,
+			"fixed",
+			SystemStage::parallel()
+				.with_run_criteria(step.system())
+				.with_system(receive.system()),
+		)
+		.add_event_set_with::<MyEvents>(EventSetConfig {
+			lock_step: Some("fixed"),
+			..Default::default()
+		})
+		.add_resource(Step(false))
+		.add_resource(Received::default());
+
+		app.resources().event_set::<MyEvents>().send(TestEvent);
+		for _ in 0..3 {
+			app.app.update();
+		}
+		assert_eq!(count(&app.app), 1);
+
+		app.resources_mut().insert(Step(true));
+		app.app.update();
+		app.app.update();
+		assert_eq!(count(&app.app), 1);
+
+		app.app.update();
+		assert_eq!(count(&app.app), 0);
+		assert_eq!(app.resources().get::<Received>().unwrap().0, 1);
+	}
+
+	#[test]
+	fn stage() {
+		use bevy::app::{stage, App, Events};
+		use bevy::ecs::{IntoSystem, Local, Resources};
+
+		struct TestEvent1;
+		struct TestEvent2;
+		struct TestEvent3;
+		struct TestEvent4;
+
+		event_set!(DefaultEvents { TestEvent1 });
+		event_set!(
+			#[stage(stage::LAST)]
+			LateEvents { TestEvent2 }
+		);
+		event_set!(OtherEvents { TestEvent4 });
+
+		#[events(stage = stage::POST_UPDATE, TestEvent3)]
+		struct AttributeEvents;
+
+		fn emit(
+			mut default: DefaultEvents,
+			mut late: LateEvents,
+			mut attribute: AttributeEvents,
+			mut other: OtherEvents,
+			mut sent: Local<bool>,
+		) {
+			if !*sent {
+				default.send(TestEvent1);
+				late.send(TestEvent2);
+				attribute.send(TestEvent3);
+				other.send(TestEvent4);
+				*sent = true;
+			}
+		}
+
+		fn count<T: Send + Sync + 'static>(resources: &Resources) -> usize {
+			let events = resources.get::<Events<T>>().unwrap();
+			events.get_reader().iter(&events).count()
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<DefaultEvents>()
+			.add_event_set::<LateEvents>()
+			.add_event_set::<AttributeEvents>()
+			.add_event_set_in_stage::<OtherEvents>(stage::LAST)
+			.add_system_to_stage(stage::UPDATE, emit.system());
+		app.app.update();
+		app.app.update();
+
+		assert_eq!(count::<TestEvent1>(app.resources()), 1);
+		assert_eq!(count::<TestEvent2>(app.resources()), 0);
+		assert_eq!(count::<TestEvent3>(app.resources()), 0);
+		assert_eq!(count::<TestEvent4>(app.resources()), 0);
+	}
+
+	#[test]
+	fn introspection() {
+		use bevy::app::{stage, App};
+		use bevy::ecs::{IntoSystem, ResMut};
+		use std::any::TypeId;
+
+		struct TestEvent1;
+		struct TestEvent2;
+		event_set!(MyEvents {
+			TestEvent1,
+			TestEvent2
+		});
+
+		#[derive(Default)]
+		struct Lengths(Vec<(usize, usize, usize, bool)>);
+
+		fn emit(mut events: MyEvents, mut lengths: ResMut<Lengths>) {
+			let mut record = |events: &MyEvents| {
+				lengths.0.push((
+					events.len_of::<TestEvent1>(),
+					events.len_of::<TestEvent2>(),
+					events.total_len(),
+					events.is_empty(),
+				));
+			};
+
+			record(&events);
+			events.send(TestEvent1);
+			events.send(TestEvent2);
+			events.send(TestEvent1);
+			record(&events);
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<MyEvents>()
+			.add_resource(Lengths::default())
+			.add_system_to_stage(stage::UPDATE, emit.system());
+		app.app.update();
+
+		let lengths = app.resources().get::<Lengths>().unwrap();
+		assert_eq!(lengths.0, vec![(0, 0, 0, true), (2, 1, 3, false)]);
+
+		assert_eq!(MyEvents::type_names().len(), 2);
+		assert!(MyEvents::type_names()[0].ends_with("TestEvent1"));
+		assert!(MyEvents::type_names()[1].ends_with("TestEvent2"));
+		assert_eq!(
+			MyEvents::type_ids(),
+			&[TypeId::of::<TestEvent1>(), TypeId::of::<TestEvent2>()]
+		);
+	}
+
+	#[test]
+	fn clear() {
+		use bevy::app::stage;
+		use bevy::ecs::{IntoSystem, ResMut};
+
+		#[derive(Default)]
+		struct Drained(Vec<usize>);
+
+		fn emit(mut events: TestEvents) {
+			events.send(TestEvent1(1));
+			events.send(TestEvent2(2));
+			events.clear::<TestEvent1>();
+			assert_eq!(events.total_len(), 1);
+
+			events.send(TestEvent2(3));
+			events.send(TestEvent1(4));
+		}
+
+		fn drain(mut events: TestEvents, mut drained: ResMut<Drained>) {
+			events.clear::<TestEvent2>();
+			events.send_batch(vec![TestEvent2(5), TestEvent2(6)]);
+			events.send(TestEvent1(7));
+			drained
+				.0
+				.extend(events.drain_all().map(|event| match event {
+					TestEventsAny::TestEvent1(event) => event.0,
+					TestEventsAny::TestEvent2(event) => event.0,
+					TestEventsAny::TestEvent3(event) => event.0,
+				}));
+			assert!(events.is_empty());
+
+			events.send(TestEvent1(8));
+			events.clear_all();
+			assert!(events.is_empty());
+		}
+
+		let mut app = test_app();
+		app.add_resource(Drained::default())
+			.add_system_to_stage(stage::PRE_UPDATE, emit.system())
+			.add_system_to_stage(stage::POST_UPDATE, drain.system());
+		app.app.update();
+
+		assert_eq!(received(&app), vec![2, 3, 4]);
+
+		let drained = app.resources().get::<Drained>().unwrap();
+		assert_eq!(drained.0, vec![4, 5, 6, 7]);
+	}
+
+	#[test]
+	fn log() {
+		use bevy::app::{App, Events};
+		use bevy::log::Level;
+
+		#[derive(Debug)]
+		struct TestEvent1;
+		#[derive(Debug)]
+		#[allow(dead_code)]
+		struct TestEvent2(usize);
+		event_set!(MyEvents {
+			TestEvent1,
+			TestEvent2
+		});
+
+		let mut app = App::build();
+		app.add_event_set_with::<MyEvents>(EventSetConfig {
+			retention: Retention::UntilRead,
+			..Default::default()
+		})
+		.add_plugin(
+			EventSetLogPlugin::<MyEvents>::new()
+				.with_level(Level::DEBUG)
+				.with_type_level::<TestEvent2>(Level::TRACE)
+				.without::<TestEvent1>(),
+		);
+
+		let mut events = app.resources().event_set::<MyEvents>();
+		events.send(TestEvent1);
+		events.send(TestEvent2(1));
+		for _ in 0..3 {
+			app.app.update();
+		}
+
+		// Logging doesn't count as reading the events
+		let events = app.resources().get::<Events<TestEvent2>>().unwrap();
+		assert_eq!(events.get_reader().iter(&events).count(), 1);
+	}
+
+	/// The event set that the record and replay tests record
+	#[cfg(feature = "serde")]
+	mod recording {
+		use super::*;
+		use bevy::app::{stage, App};
+		use bevy::ecs::{IntoSystem, Local};
+		use serde_crate::{Deserialize, Serialize};
+		use std::io::{self, Write};
+		use std::sync::{Arc, Mutex};
+
+		#[derive(Serialize, Deserialize)]
+		#[serde(crate = "serde_crate")]
+		pub struct TestEvent1(pub usize);
+		#[derive(Serialize, Deserialize)]
+		#[serde(crate = "serde_crate")]
+		pub struct TestEvent2 {
+			pub number: usize,
+		}
+		event_set!(pub MyEvents {
+			TestEvent1,
+			TestEvent2
+		});
+
+		#[derive(Clone, Default)]
+		struct Output(Arc<Mutex<Vec<u8>>>);
+
+		impl Write for Output {
+			fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+				self.0.lock().unwrap().write(buf)
+			}
+
+			fn flush(&mut self) -> io::Result<()> {
+				Ok(())
+			}
+		}
+
+		/// Records three frames of events, with no events in the second frame
+		pub fn record(format: RecordFormat) -> Vec<u8> {
+			fn emit(mut events: MyEvents, mut frame: Local<usize>) {
+				if *frame != 1 {
+					events.send(TestEvent2 { number: *frame });
+					events.send(TestEvent1(*frame + 10));
+				}
+				*frame += 1;
+			}
+
+			let output = Output::default();
+			let mut app = App::build();
+			app.add_event_set::<MyEvents>()
+				.add_plugin(EventSetRecorder::<MyEvents>::to_writer(
+					output.clone(),
+					format,
+				))
+				.add_system_to_stage(stage::UPDATE, emit.system());
+			for _ in 0..3 {
+				app.app.update();
+			}
+
+			let recording = output.0.lock().unwrap().clone();
+			recording
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn record() {
+		let output = String::from_utf8(recording::record(RecordFormat::Json)).unwrap();
+		assert_eq!(
+			output.lines().collect::<Vec<_>>(),
+			vec![
+				r#"{"frame":0,"order":0,"event":{"TestEvent2":{"number":0}}}"#,
+				r#"{"frame":0,"order":1,"event":{"TestEvent1":10}}"#,
+				r#"{"frame":2,"order":2,"event":{"TestEvent2":{"number":2}}}"#,
+				r#"{"frame":2,"order":3,"event":{"TestEvent1":12}}"#,
+			]
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn replay() {
+		use bevy::app::{stage, App};
+		use bevy::ecs::{IntoSystem, ResMut};
+		use recording::{MyEvents, MyEventsReader, MyEventsRef, TestEvent1};
+
+		#[derive(Default)]
+		struct Received(Vec<Vec<usize>>);
+
+		fn emit_live(mut events: MyEvents) {
+			events.send(TestEvent1(100));
+		}
+
+		fn receive(mut events: MyEventsReader, mut received: ResMut<Received>) {
+			received.0.push(
+				events
+					.iter_all()
+					.map(|event| match event {
+						MyEventsRef::TestEvent1(event) => event.0,
+						MyEventsRef::TestEvent2(event) => event.number,
+					})
+					.collect(),
+			);
+		}
+
+		for &format in &[RecordFormat::Ron, RecordFormat::Json, RecordFormat::Binary] {
+			let data = recording::record(format);
+			let replay = EventSetReplay::<MyEvents>::from_reader(data.as_slice(), format)
+				.unwrap()
+				.suppress_live(true);
+
+			let mut app = App::build();
+			app.add_event_set::<MyEvents>()
+				.add_plugin(replay)
+				.add_resource(Received::default())
+				.add_system_to_stage(stage::PRE_UPDATE, emit_live.system())
+				.add_system_to_stage(stage::UPDATE, receive.system());
+			for _ in 0..4 {
+				app.app.update();
+			}
+
+			let received = app.resources().get::<Received>().unwrap();
+			assert_eq!(
+				received.0,
+				vec![vec![0, 10], vec![], vec![2, 12], vec![100]],
+				"{:?}",
+				format
+			);
+		}
+	}
+
+	#[test]
+	fn probe() {
+		use bevy::app::{stage, App};
+		use bevy::ecs::{IntoSystem, Local};
+
+		#[derive(Clone, Debug, PartialEq)]
+		struct TestEvent1(usize);
+		#[derive(Clone, Debug, PartialEq)]
+		struct TestEvent2(usize);
+		#[derive(Clone)]
+		struct TestEvent3;
+		event_set!(MyEvents {
+			TestEvent1,
+			TestEvent2,
+			TestEvent3
+		});
+
+		fn emit(mut events: MyEvents, mut frame: Local<usize>) {
+			events.send(TestEvent2(*frame));
+			events.send(TestEvent1(*frame));
+			*frame += 1;
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<MyEvents>()
+			.add_system_to_stage(stage::UPDATE, emit.system());
+		let probe = EventSetProbe::<MyEvents>::attach(&mut app);
+		app.app.update();
+		app.app.update();
+
+		assert_eq!(
+			probe.sent::<TestEvent1>(),
+			vec![TestEvent1(0), TestEvent1(1)]
+		);
+		probe.assert_sent::<TestEvent2>(|event| event.0 == 1);
+		probe.assert_none::<TestEvent3>();
+		probe
+			.assert_order()
+			.then::<TestEvent2>()
+			.then_matching::<TestEvent1>(|event| event.0 == 0)
+			.then::<TestEvent2>();
+
+		probe.clear();
+		app.app.update();
+		assert_eq!(probe.sent::<TestEvent2>(), vec![TestEvent2(2)]);
+	}
+
+	#[test]
+	fn mock() {
+		use bevy::app::{stage, App};
+		use bevy::ecs::IntoSystem;
+
+		#[derive(Debug, PartialEq)]
+		struct TestEvent1(usize);
+		#[derive(Debug, PartialEq)]
+		struct TestEvent2(usize);
+		event_set!(MyEvents {
+			TestEvent1,
+			TestEvent2
+		});
+
+		fn take_damage(events: &mut impl MyEventsSink, health: usize) {
+			events.send(TestEvent1(health));
+			if health == 0 {
+				events.send_any(TestEvent2(1).into());
+			}
+			events.send_batch(vec![TestEvent1(1), TestEvent1(2)]);
+		}
+
+		let mut events = MyEventsMock::default();
+		take_damage(&mut events, 0);
+		assert_eq!(
+			events.sent::<TestEvent1>(),
+			&[TestEvent1(0), TestEvent1(1), TestEvent1(2)]
+		);
+		assert_eq!(events.sent::<TestEvent2>(), &[TestEvent2(1)]);
+		assert_eq!(events.total_len(), 4);
+
+		let drained = events
+			.drain_all()
+			.map(|event| match event {
+				MyEventsAny::TestEvent1(event) => event.0,
+				MyEventsAny::TestEvent2(event) => event.0 + 10,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(drained, vec![0, 11, 1, 2]);
+		assert!(events.is_empty());
+
+		fn damage_system(mut events: MyEvents) {
+			take_damage(&mut events, 5);
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<MyEvents>()
+			.add_system_to_stage(stage::UPDATE, damage_system.system());
+		take_damage(&mut app.resources().event_set::<MyEvents>(), 5);
+		app.app.update();
+	}
+
+	#[test]
+	fn attribute() {
+		struct TestEvent1;
+		struct TestEvent2;
+
+		/// An event set made with the attribute
+		#[events(TestEvent1, TestEvent2)]
+		#[cfg(test)]
+		pub(crate) struct MyEvents;
+
+		#[allow(dead_code)]
+		fn emit(mut events: MyEvents, mut reader: MyEventsReader) {
+			events.send(TestEvent1);
+			events.send_any(MyEventsAny::TestEvent2(TestEvent2));
+			reader.iter::<TestEvent1>();
+		}
+	}
+
+	#[test]
+	fn attributes() {
+		mod private {
+			pub struct TestEvent;
+
+			event_set!(
+				/// A private event set
+				#[allow(unused)]
+				pub(super) MyEvents { TestEvent }
+			);
+		}
+
+		#[allow(dead_code)]
+		fn emit(mut events: private::MyEvents) {
+			events.send(private::TestEvent);
+		}
+
+		event_set!(
+			#[cfg(any())]
+			NotCompiled { DoesNotExist }
+		);
+	}
+
+	#[test]
+	fn nested() {
+		use bevy::app::{App, Events};
+
+		mod input {
+			pub struct Aim;
+		}
+
+		mod audio {
+			#[allow(dead_code)]
+			pub struct Volume(pub f32);
+			event_set!(pub(crate) AudioEvents { Volume });
+		}
+
+		use audio::Volume;
+
+		struct Jump;
+		struct Crouch;
+		#[allow(dead_code)]
+		struct Score(usize);
+
+		event_set!(InputEvents { Jump, Crouch, input::Aim });
+		event_set!(KeyEvents { ..InputEvents });
+		event_set!(GameEvents {
+			..KeyEvents,
+			..audio::AudioEvents,
+			Score,
+		});
+
+		#[events(..KeyEvents, Score)]
+		struct ScriptEvents;
+
+		#[allow(dead_code)]
+		fn emit(mut events: GameEvents) {
+			events.send(Jump);
+			events.send(Crouch);
+			events.send(input::Aim);
+			events.send(Volume(1.0));
+			events.send(Score(10));
+			events.send_any(GameEventsAny::Aim(input::Aim));
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<InputEvents>()
+			.add_event_set::<GameEvents>()
+			.add_event_set::<ScriptEvents>();
+
+		app.resources_mut()
+			.get_mut::<Events<Jump>>()
+			.unwrap()
+			.send(Jump);
+		app.app.update();
+
+		let events = app.resources().get::<Events<Jump>>().unwrap();
+		assert_eq!(events.get_reader().iter(&events).count(), 1);
+	}
+
+	#[test]
+	fn nested_order() {
+		use bevy::app::{stage, App};
+		use bevy::ecs::{IntoSystem, ResMut};
+
+		struct Jump(usize);
+		struct Crouch(usize);
+		struct Score(usize);
+
+		event_set!(InputEvents { Jump, Crouch });
+		event_set!(GameEvents { ..InputEvents, Score });
+
+		fn jump(mut events: InputEvents) {
+			events.send(Jump(1));
+		}
+
+		fn score(mut events: GameEvents) {
+			events.send(Score(2));
+		}
+
+		fn crouch(mut events: InputEvents) {
+			events.send(Crouch(3));
+		}
+
+		fn receive(mut events: GameEventsReader, mut received: ResMut<Received>) {
+			received
+				.0
+				.extend(events.iter_all().map(|event| match event {
+					GameEventsRef::Jump(e) => e.0,
+					GameEventsRef::Crouch(e) => e.0,
+					GameEventsRef::Score(e) => e.0,
+				}));
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<InputEvents>()
+			.add_event_set::<GameEvents>()
+			.add_resource(Received::default())
+			.add_system_to_stage(stage::PRE_UPDATE, jump.system())
+			.add_system_to_stage(stage::UPDATE, score.system())
+			.add_system_to_stage(stage::POST_UPDATE, crouch.system())
+			.add_system_to_stage(stage::LAST, receive.system());
+		app.app.update();
+
+		// The outer set doesn't know the order of the events sent through the nested set
+		assert_eq!(received(&app), vec![2, 1, 3]);
+	}
+
+	#[test]
+	fn paths() {
+		mod input {
+			pub struct Jump;
+			pub mod keys {
+				#[allow(dead_code)]
+				pub struct Crouch(pub bool);
+			}
+		}
+
+		event_set!(MyEvents {
+			input::Jump,
+			input::keys::Crouch,
+		});
+	}
+
+	#[test]
+	fn many() {
+		use bevy::app::App;
+
+		macro_rules! events {
+			($($event:ident)*) => {
+				$(#[allow(dead_code)] pub struct $event;)*
+			};
+		}
+
+		events!(E0 E1 E2 E3 E4 E5 E6 E7 E8 E9 E10 E11 E12 E13 E14 E15 E16 E17 E18 E19 E20 E21 E22 E23 E24 E25 E26 E27 E28 E29 E30 E31 E32 E33 E34 E35 E36 E37 E38 E39 E40 E41 E42 E43 E44 E45 E46 E47 E48 E49 E50 E51 E52 E53 E54 E55 E56 E57 E58 E59 E60 E61 E62 E63);
+
+		mod a {
+			pub mod b {
+				events!(E0 E1 E2 E3 E4 E5 E6 E7 E8 E9 E10 E11 E12 E13 E14 E15 E16 E17 E18 E19 E20 E21 E22 E23 E24 E25 E26 E27 E28 E29 E30 E31 E32 E33 E34 E35 E36 E37 E38 E39);
+			}
+		}
+
+		event_set!(PlainEvents {
+			E0,
+			E1,
+			E2,
+			E3,
+			E4,
+			E5,
+			E6,
+			E7,
+			E8,
+			E9,
+			E10,
+			E11,
+			E12,
+			E13,
+			E14,
+			E15,
+			E16,
+			E17,
+			E18,
+			E19,
+			E20,
+			E21,
+			E22,
+			E23,
+			E24,
+			E25,
+			E26,
+			E27,
+			E28,
+			E29,
+			E30,
+			E31,
+			E32,
+			E33,
+			E34,
+			E35,
+			E36,
+			E37,
+			E38,
+			E39,
+			E40,
+			E41,
+			E42,
+			E43,
+			E44,
+			E45,
+			E46,
+			E47,
+			E48,
+			E49,
+			E50,
+			E51,
+			E52,
+			E53,
+			E54,
+			E55,
+			E56,
+			E57,
+			E58,
+			E59,
+			E60,
+			E61,
+			E62,
+			E63,
+		});
+
+		event_set!(PathEvents {
+			a::b::E0, a::b::E1, a::b::E2, a::b::E3, a::b::E4, a::b::E5, a::b::E6, a::b::E7,
+			a::b::E8, a::b::E9, a::b::E10, a::b::E11, a::b::E12, a::b::E13, a::b::E14, a::b::E15,
+			a::b::E16, a::b::E17, a::b::E18, a::b::E19, a::b::E20, a::b::E21, a::b::E22, a::b::E23,
+			a::b::E24, a::b::E25, a::b::E26, a::b::E27, a::b::E28, a::b::E29, a::b::E30, a::b::E31,
+			a::b::E32, a::b::E33, a::b::E34, a::b::E35, a::b::E36, a::b::E37, a::b::E38, a::b::E39,
+		});
+
+		#[events(
+			a::b::E0, a::b::E1, a::b::E2, a::b::E3, a::b::E4, a::b::E5, a::b::E6, a::b::E7,
+			a::b::E8, a::b::E9, a::b::E10, a::b::E11, a::b::E12, a::b::E13, a::b::E14, a::b::E15,
+			a::b::E16, a::b::E17, a::b::E18, a::b::E19, a::b::E20, a::b::E21, a::b::E22, a::b::E23,
+			a::b::E24, a::b::E25, a::b::E26, a::b::E27, a::b::E28, a::b::E29, a::b::E30, a::b::E31,
+			a::b::E32, a::b::E33, a::b::E34, a::b::E35, a::b::E36, a::b::E37, a::b::E38, a::b::E39
+		)]
+		struct AttributeEvents;
+
+		App::build()
+			.add_event_set::<PlainEvents>()
+			.add_event_set::<PathEvents>()
+			.add_event_set::<AttributeEvents>();
+
+		assert_eq!(PlainEvents::type_ids().len(), 64);
+		assert_eq!(PathEvents::type_ids().len(), 40);
+		assert_eq!(AttributeEvents::type_ids(), PathEvents::type_ids());
+	}
+
+	#[test]
+	fn generics() {
+		struct Packet<T>(T);
+		struct Ping;
+		struct Pong;
+
+		event_set!(MyEvents {
+			Packet<Ping> as PingPacket,
+			Packet<Pong> as PongPacket,
+			Option<Ping> as MaybePing,
+		});
+	}
+
+	#[test]
+	fn generic_attribute() {
+		use bevy::app::App;
+		use bevy::ecs::{IntoSystem, Local, ResMut};
+
+		struct Packet<T>(T);
+		struct Ping;
+		struct Pong;
+		struct Disconnect;
+
+		#[events(Packet<T> as Packet, Disconnect)]
+		struct NetEvents<T: Send + Sync + 'static>;
+
+		fn emit(mut events: NetEvents<Ping>, mut sent: Local<bool>) {
+			if !*sent {
+				events.send(Packet(Ping));
+				events.send_any(NetEventsAny::Disconnect(Disconnect));
+				*sent = true;
+			}
+		}
+
+		fn read(mut reader: NetEventsReader<Ping>, mut read: ResMut<Vec<&'static str>>) {
+			for event in reader.iter_all() {
+				read.push(match event {
+					NetEventsRef::Packet(_) => "packet",
+					NetEventsRef::Disconnect(_) => "disconnect",
+				});
+			}
+		}
+
+		let mut app = App::build();
+		app.add_event_set::<NetEvents<Ping>>()
+			.add_event_set::<NetEvents<Pong>>()
+			.add_resource(Vec::<&'static str>::new())
+			.add_system(emit.system())
+			.add_system(read.system());
+		app.app.update();
+
+		let read = app.resources().get::<Vec<&'static str>>().unwrap();
+		assert_eq!(*read, vec!["packet", "disconnect"]);
+
+		assert_ne!(NetEvents::<Ping>::type_ids(), NetEvents::<Pong>::type_ids());
+
+		let mut mock = NetEventsMock::<Pong>::default();
+		mock.send(Packet(Pong));
+		assert_eq!(mock.sent::<Packet<Pong>>().len(), 1);
+	}
+
+	#[test]
+	fn crate_path() {
+		struct TestEvent;
+
+		#[events(crate = crate, TestEvent)]
+		struct MyEvents;
+
+		#[allow(dead_code)]
+		fn emit(mut events: MyEvents) {
+			events.send(TestEvent);
+		}
 	}
 }
@@ -0,0 +1,138 @@
+//! Logging the events of an event set
+//!
+//! See [`EventSetLogPlugin`] for the documentation.
+
+use crate::__private::EventBuffer;
+use bevy::app::{AppBuilder, Plugin};
+use bevy::log::{debug, error, info, trace, warn, Level};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+/// Logs every event of an event set with its type name and `Debug` representation
+///
+/// The events are logged at the end of each frame, in the order they were
+/// sent. All event types in the set must implement `Debug`. Add the event set
+/// to the app before adding the plugin.
+///
+/// Events are logged at the `INFO` level by default. The level can be changed
+/// for the whole set or for each event type, and types can be left out.
+///
+/// # Example
+/// ```
+/// use bevy::log::Level;
+/// use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// #[derive(Debug)]
+/// struct Jump;
+/// #[derive(Debug)]
+/// struct Move(f32, f32);
+/// #[derive(Debug)]
+/// struct Look(f32, f32);
+/// event_set!(InputEvents { Jump, Move, Look });
+///
+/// App::build()
+///     .add_event_set::<InputEvents>()
+///     .add_plugin(
+///         EventSetLogPlugin::<InputEvents>::new()
+///             .with_level(Level::DEBUG)
+///             .with_type_level::<Move>(Level::TRACE)
+///             .without::<Look>(),
+///     );
+/// ```
+pub struct EventSetLogPlugin<S> {
+	filter: LogFilter,
+	marker: PhantomData<fn() -> S>,
+}
+
+impl<S> EventSetLogPlugin<S> {
+	/// Creates a plugin that logs all events of the set at the `INFO` level
+	pub fn new() -> Self {
+		EventSetLogPlugin {
+			filter: LogFilter::new(Level::INFO),
+			marker: PhantomData,
+		}
+	}
+
+	/// Sets the level that events are logged at
+	pub fn with_level(mut self, level: Level) -> Self {
+		self.filter.level = level;
+		self
+	}
+
+	/// Sets the level that events of the given type are logged at
+	pub fn with_type_level<T: 'static>(mut self, level: Level) -> Self
+	where
+		S: EventBuffer<T>,
+	{
+		self.filter.types.insert(TypeId::of::<T>(), Some(level));
+		self
+	}
+
+	/// Stops events of the given type from being logged
+	pub fn without<T: 'static>(mut self) -> Self
+	where
+		S: EventBuffer<T>,
+	{
+		self.filter.types.insert(TypeId::of::<T>(), None);
+		self
+	}
+}
+
+impl<S> Default for EventSetLogPlugin<S> {
+	fn default() -> Self {
+		EventSetLogPlugin::new()
+	}
+}
+
+impl<S: LogEventSet + 'static> Plugin for EventSetLogPlugin<S> {
+	fn build(&self, app: &mut AppBuilder) {
+		S::add_log_system(app, self.filter.clone());
+	}
+}
+
+/// Adds a system that logs the events of an event set
+///
+/// Only implemented for event sets where all event types implement `Debug`.
+#[doc(hidden)]
+pub trait LogEventSet {
+	fn add_log_system(app: &mut AppBuilder, filter: LogFilter);
+}
+
+/// The levels that the events of an event set are logged at
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct LogFilter {
+	pub level: Level,
+	pub types: HashMap<TypeId, Option<Level>>,
+}
+
+impl LogFilter {
+	pub fn new(level: Level) -> Self {
+		LogFilter {
+			level,
+			types: HashMap::new(),
+		}
+	}
+}
+
+/// Logs an event of type `T`, unless the filter leaves its type out
+#[doc(hidden)]
+pub fn log_event<T: 'static>(filter: &LogFilter, event: &dyn Debug) {
+	let level = match filter.types.get(&TypeId::of::<T>()) {
+		Some(Some(level)) => *level,
+		Some(None) => return,
+		None => filter.level,
+	};
+
+	let name = std::any::type_name::<T>();
+	match level {
+		Level::TRACE => trace!("{}: {:?}", name, event),
+		Level::DEBUG => debug!("{}: {:?}", name, event),
+		Level::INFO => info!("{}: {:?}", name, event),
+		Level::WARN => warn!("{}: {:?}", name, event),
+		_ => error!("{}: {:?}", name, event),
+	}
+}
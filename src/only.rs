@@ -0,0 +1,153 @@
+//! Event sets narrowed down to some of their event types
+//!
+//! See [`Only`] for the documentation.
+
+use crate::__private::{is_suppressed, OrderIds, ReplaySuppression, SendOrder, SendToResources};
+use crate::{EventSetOf, EventTuple, SendAnyEvent, SendEvent};
+use bevy::app::Events;
+use bevy::ecs::{Component, Res, ResMut, Resources, SystemParam, SystemState, World};
+use std::marker::PhantomData;
+
+/// An event set that can only send some of its event types
+///
+/// A system that takes an event set gets write access to the event buffers of
+/// all its types, so it can't run in parallel with any system that reads one
+/// of them. This only gets write access to the types in the tuple `L`, so the
+/// scheduler can run it next to the readers of the other types.
+///
+/// By default, the events are left out of the send order of the set `S`.
+/// Readers of the set see them like events sent directly to an event buffer,
+/// in the place of the next event of the same type. Pass [`Ordered`] as the
+/// third parameter to record them in the order. That order is kept in a buffer
+/// that all systems sending to or reading from the set use, so an ordered
+/// `Only` can't run in parallel with any of those systems.
+///
+/// # Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct EventOne;
+/// struct EventTwo;
+/// event_set!(MyEvents { EventOne, EventTwo });
+///
+/// fn event_one_emitter_system(mut events: Only<MyEvents, (EventOne,)>) {
+///     events.send(EventOne);
+/// }
+///
+/// fn event_two_emitter_system(mut events: Only<MyEvents, (EventTwo,), Ordered>) {
+///     events.send(EventTwo);
+/// }
+/// ```
+pub struct Only<'a, S, L, O = Unordered>
+where
+	S: SendAnyEvent,
+	S::Any: Component,
+	L: EventTuple<'a>,
+	O: OrderMode,
+{
+	events: EventSetOf<'a, L>,
+	order: Option<ResMut<'a, Events<SendOrder<S::Any>>>>,
+	order_ids: Option<Res<'a, OrderIds<S::Any>>>,
+	suppression: Option<Res<'a, ReplaySuppression<S::Any>>>,
+	marker: PhantomData<fn() -> O>,
+}
+
+/// Chooses if the events sent through [`Only`] are recorded in the send order of the set
+pub trait OrderMode {
+	/// Whether the events are recorded in the send order
+	const ORDERED: bool;
+}
+
+/// Records the events sent through [`Only`] in the send order of the set
+pub struct Ordered;
+
+impl OrderMode for Ordered {
+	const ORDERED: bool = true;
+}
+
+/// Leaves the events sent through [`Only`] out of the send order of the set
+///
+/// This is the default.
+pub struct Unordered;
+
+impl OrderMode for Unordered {
+	const ORDERED: bool = false;
+}
+
+impl<'a, S, L, O> SystemParam for Only<'a, S, L, O>
+where
+	S: SendAnyEvent,
+	S::Any: Component,
+	L: EventTuple<'a>,
+	O: OrderMode,
+{
+	fn init(system_state: &mut SystemState, world: &World, resources: &mut Resources) {
+		EventSetOf::<'a, L>::init(system_state, world, resources);
+		if O::ORDERED {
+			ResMut::<'a, Events<SendOrder<S::Any>>>::init(system_state, world, resources);
+			Res::<'a, OrderIds<S::Any>>::init(system_state, world, resources);
+		}
+		Option::<Res<'a, ReplaySuppression<S::Any>>>::init(system_state, world, resources);
+	}
+
+	unsafe fn get_param(
+		system_state: &SystemState,
+		world: &World,
+		resources: &Resources,
+	) -> Option<Self> {
+		let (order, order_ids) = if O::ORDERED {
+			(
+				Some(ResMut::get_param(system_state, world, resources)?),
+				Some(Res::get_param(system_state, world, resources)?),
+			)
+		} else {
+			(None, None)
+		};
+
+		Some(Only {
+			events: EventSetOf::get_param(system_state, world, resources)?,
+			order,
+			order_ids,
+			suppression: Option::get_param(system_state, world, resources)?,
+			marker: PhantomData,
+		})
+	}
+}
+
+impl<'a, S, L, O, T, I> SendEvent<T, I> for Only<'a, S, L, O>
+where
+	S: SendAnyEvent + SendToResources<T>,
+	S::Any: Component,
+	L: EventTuple<'a>,
+	O: OrderMode,
+	EventSetOf<'a, L>: SendEvent<T, I>,
+	T: 'static,
+{
+	fn send(&mut self, event: T) {
+		if is_suppressed(self.suppression.as_deref()) {
+			return;
+		}
+
+		self.events.send(event);
+		if let (Some(order), Some(ids)) = (&mut self.order, &self.order_ids) {
+			order.send(ids.of::<T>());
+		}
+	}
+
+	fn send_batch<E: IntoIterator<Item = T>>(&mut self, events: E) {
+		if is_suppressed(self.suppression.as_deref()) {
+			return;
+		}
+
+		let mut count = 0;
+		self.events
+			.send_batch(events.into_iter().inspect(|_| count += 1));
+
+		if let (Some(order), Some(ids)) = (&mut self.order, &self.order_ids) {
+			if count > 0 {
+				order.send(ids.batch::<T>(count));
+			}
+		}
+	}
+}
@@ -0,0 +1,171 @@
+//! Keeping track of the order that the events of an event set are sent in
+
+use bevy::app::{EventReader, Events};
+use bevy::ecs::Component;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Records the type of one or more events sent in a row through the event set with sum enum `A`
+///
+/// An entry with a count of `0` records that the events of its type were
+/// cleared, which drops the earlier entries of that type.
+pub struct SendOrder<A> {
+	id: u64,
+	type_id: TypeId,
+	count: usize,
+	marker: PhantomData<fn() -> A>,
+}
+
+impl<A> SendOrder<A> {
+	pub fn is<T: 'static>(&self) -> bool {
+		self.type_id == TypeId::of::<T>()
+	}
+
+	pub fn count(&self) -> usize {
+		self.count
+	}
+}
+
+/// Gives out the ids of the send order entries of the event set with sum enum `A`
+///
+/// Readers use the ids to tell which entries they have already seen, as the
+/// event buffers don't expose the ids of their events.
+pub struct OrderIds<A> {
+	next: AtomicU64,
+	marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Default for OrderIds<A> {
+	fn default() -> Self {
+		OrderIds {
+			next: AtomicU64::new(0),
+			marker: PhantomData,
+		}
+	}
+}
+
+impl<A> OrderIds<A> {
+	pub fn of<T: 'static>(&self) -> SendOrder<A> {
+		self.batch::<T>(1)
+	}
+
+	pub fn cleared<T: 'static>(&self) -> SendOrder<A> {
+		self.batch::<T>(0)
+	}
+
+	/// Gets the id that the next entry will get
+	pub fn next_id(&self) -> u64 {
+		self.next.load(Ordering::Relaxed)
+	}
+
+	pub fn batch<T: 'static>(&self, count: usize) -> SendOrder<A> {
+		SendOrder {
+			id: self.next.fetch_add(1, Ordering::Relaxed),
+			type_id: TypeId::of::<T>(),
+			count,
+			marker: PhantomData,
+		}
+	}
+}
+
+/// Leaves out the entries of types that were cleared after them
+pub fn live<'o, A: 'o>(
+	entries: impl IntoIterator<Item = &'o SendOrder<A>>,
+) -> Vec<&'o SendOrder<A>> {
+	let entries: Vec<_> = entries.into_iter().collect();
+
+	let mut cleared = HashMap::new();
+	for entry in &entries {
+		if entry.count == 0 {
+			cleared.insert(entry.type_id, entry.id);
+		}
+	}
+
+	entries
+		.into_iter()
+		.filter(|entry| entry.count > 0)
+		.filter(|entry| !matches!(cleared.get(&entry.type_id), Some(id) if *id > entry.id))
+		.collect()
+}
+
+/// Keeps track of the send order entries that a reader of the event set with sum enum `A` has seen
+pub struct OrderReader<A> {
+	last: Option<u64>,
+	marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Default for OrderReader<A> {
+	fn default() -> Self {
+		OrderReader {
+			last: None,
+			marker: PhantomData,
+		}
+	}
+}
+
+impl<A: Component> OrderReader<A> {
+	/// Gets the entries that this reader hasn't seen yet, without marking them as seen
+	pub fn unread<'o>(&self, order: &'o Events<SendOrder<A>>) -> Vec<&'o SendOrder<A>> {
+		let last = self.last;
+		live(order.get_reader().iter(order))
+			.into_iter()
+			.filter(|entry| Some(entry.id) > last)
+			.collect()
+	}
+
+	/// Marks the given entries as seen
+	pub fn mark(&mut self, entries: &[&SendOrder<A>]) {
+		if let Some(entry) = entries.last() {
+			self.last = Some(entry.id);
+		}
+	}
+}
+
+/// Reads the events of type `T` for a reader of an event set
+///
+/// Reading the events of one type moves a cursor past the send order entries
+/// that were made so far, reading the events of all types in order skips the
+/// events of the entries before the cursor.
+pub struct TypeReader<T> {
+	reader: EventReader<T>,
+	cursor: u64,
+}
+
+impl<T> Default for TypeReader<T> {
+	fn default() -> Self {
+		TypeReader {
+			reader: EventReader::default(),
+			cursor: 0,
+		}
+	}
+}
+
+impl<T: Component> TypeReader<T> {
+	/// Iterates over the unseen events, without moving the cursor
+	pub fn iter<'e>(&mut self, events: &'e Events<T>) -> impl DoubleEndedIterator<Item = &'e T> {
+		self.reader.iter(events)
+	}
+
+	/// Iterates over the unseen events and moves the cursor past the send order entries made so far
+	pub fn read<'e, A>(
+		&mut self,
+		events: &'e Events<T>,
+		ids: &OrderIds<A>,
+	) -> impl DoubleEndedIterator<Item = &'e T> {
+		// The events are sent before their entry gets an id, so all events of the
+		// entries before the cursor are read here
+		self.cursor = ids.next_id();
+		self.reader.iter(events)
+	}
+
+	/// Counts the events of a send order entry that weren't read by `read`
+	pub fn unseen<A>(&self, entry: &SendOrder<A>) -> usize {
+		if entry.id < self.cursor {
+			0
+		} else {
+			entry.count
+		}
+	}
+}
@@ -0,0 +1,214 @@
+//! Checking the events sent through an event set in tests
+//!
+//! See [`EventSetProbe`] for the documentation.
+
+use crate::__private::EventBuffer;
+use bevy::app::AppBuilder;
+use std::any::{type_name, Any, TypeId};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Keeps a copy of every event sent through an event set, to check them in tests
+///
+/// The events are copied at the end of each frame, in the order they were
+/// sent. All event types in the set must implement `Clone`. Add the event set
+/// to the app before attaching the probe.
+///
+/// # Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Damage(u32);
+/// #[derive(Clone)]
+/// struct Died;
+/// #[derive(Clone)]
+/// struct Respawned;
+/// event_set!(HealthEvents { Damage, Died, Respawned });
+///
+/// fn fall_system(mut events: HealthEvents) {
+///     events.send(Damage(100));
+///     events.send(Died);
+/// }
+///
+/// let mut app = App::build();
+/// app.add_event_set::<HealthEvents>()
+///     .add_system(fall_system.system());
+/// let probe = EventSetProbe::<HealthEvents>::attach(&mut app);
+/// app.app.update();
+///
+/// assert_eq!(probe.sent::<Damage>(), vec![Damage(100)]);
+/// probe.assert_sent::<Damage>(|damage| damage.0 >= 100);
+/// probe.assert_none::<Respawned>();
+/// probe.assert_order().then::<Damage>().then::<Died>();
+/// ```
+pub struct EventSetProbe<S> {
+	events: ProbeLog,
+	marker: PhantomData<fn() -> S>,
+}
+
+impl<S: ProbeEventSet> EventSetProbe<S> {
+	/// Adds a probe for the event set to the app
+	pub fn attach(app: &mut AppBuilder) -> Self {
+		let events = ProbeLog::default();
+		S::add_probe_system(app, events.clone());
+
+		EventSetProbe {
+			events,
+			marker: PhantomData,
+		}
+	}
+}
+
+impl<S> EventSetProbe<S> {
+	/// Gets the events of the given type that were sent, in the order they were sent
+	pub fn sent<T: Clone + 'static>(&self) -> Vec<T>
+	where
+		S: EventBuffer<T>,
+	{
+		self.events
+			.lock()
+			.iter()
+			.filter_map(|probed| probed.event.downcast_ref::<T>())
+			.cloned()
+			.collect()
+	}
+
+	/// Panics if no event of the given type that matches the predicate was sent
+	pub fn assert_sent<T: 'static>(&self, predicate: impl Fn(&T) -> bool)
+	where
+		S: EventBuffer<T>,
+	{
+		let events = self.events.lock();
+		let mut sent = events
+			.iter()
+			.filter_map(|probed| probed.event.downcast_ref::<T>());
+
+		let count = sent.clone().count();
+		if !sent.any(predicate) {
+			panic!(
+				"no `{}` event matching the predicate was sent ({} were sent)",
+				type_name::<T>(),
+				count
+			);
+		}
+	}
+
+	/// Panics if any event of the given type was sent
+	pub fn assert_none<T: 'static>(&self)
+	where
+		S: EventBuffer<T>,
+	{
+		let count = self
+			.events
+			.lock()
+			.iter()
+			.filter(|probed| probed.type_id == TypeId::of::<T>())
+			.count();
+
+		if count > 0 {
+			panic!(
+				"expected no `{}` events, but {} were sent",
+				type_name::<T>(),
+				count
+			);
+		}
+	}
+
+	/// Starts checking that events were sent in a given order
+	///
+	/// Each step looks for an event after the one found by the previous step,
+	/// so other events may be sent in between.
+	pub fn assert_order(&self) -> ProbeOrder<'_, S> {
+		ProbeOrder {
+			events: self.events.lock(),
+			next: 0,
+			marker: PhantomData,
+		}
+	}
+
+	/// Forgets the events that were sent so far
+	pub fn clear(&self) {
+		self.events.lock().clear();
+	}
+}
+
+/// Checks the order of the events seen by an [`EventSetProbe`]
+///
+/// Get one through [`EventSetProbe::assert_order`].
+pub struct ProbeOrder<'p, S> {
+	events: MutexGuard<'p, Vec<Probed>>,
+	next: usize,
+	marker: PhantomData<fn() -> S>,
+}
+
+impl<'p, S> ProbeOrder<'p, S> {
+	/// Panics if no event of the given type was sent after the event found by the previous step
+	pub fn then<T: 'static>(self) -> Self
+	where
+		S: EventBuffer<T>,
+	{
+		self.then_matching::<T>(|_| true)
+	}
+
+	/// Panics if no event of the given type that matches the predicate was sent after the event found by the previous step
+	pub fn then_matching<T: 'static>(mut self, predicate: impl Fn(&T) -> bool) -> Self
+	where
+		S: EventBuffer<T>,
+	{
+		let found = self.events[self.next..].iter().position(
+			|probed| matches!(probed.event.downcast_ref::<T>(), Some(event) if predicate(event)),
+		);
+
+		match found {
+			Some(index) => self.next += index + 1,
+			None => {
+				let previous = match self.next {
+					0 => "the start",
+					next => self.events[next - 1].type_name,
+				};
+				panic!(
+					"no matching `{}` event was sent after {}",
+					type_name::<T>(),
+					previous
+				);
+			}
+		}
+		self
+	}
+}
+
+/// Adds a system that copies the events of an event set into a probe
+///
+/// Only implemented for event sets where all event types implement `Clone`.
+#[doc(hidden)]
+pub trait ProbeEventSet {
+	fn add_probe_system(app: &mut AppBuilder, events: ProbeLog);
+}
+
+/// The events seen by a probe, shared with the system that copies them
+#[doc(hidden)]
+#[derive(Clone, Default)]
+pub struct ProbeLog(Arc<Mutex<Vec<Probed>>>);
+
+impl ProbeLog {
+	pub fn push<T: Send + 'static>(&self, event: T) {
+		self.lock().push(Probed {
+			type_id: TypeId::of::<T>(),
+			type_name: type_name::<T>(),
+			event: Box::new(event),
+		});
+	}
+
+	fn lock(&self) -> MutexGuard<'_, Vec<Probed>> {
+		self.0.lock().unwrap()
+	}
+}
+
+#[doc(hidden)]
+pub struct Probed {
+	type_id: TypeId,
+	type_name: &'static str,
+	event: Box<dyn Any + Send>,
+}
@@ -0,0 +1,162 @@
+//! Recording the events of an event set to a file
+//!
+//! See [`EventSetRecorder`] for the documentation.
+
+use bevy::app::{AppBuilder, Plugin};
+use bevy::log::error;
+use serde_crate::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Records every event sent through an event set to a file
+///
+/// The events are written at the end of each frame, in the order they were
+/// sent, as [`Recorded`] entries. All event types in the set must implement
+/// `Serialize`. Add the event set to the app before adding the recorder.
+///
+/// This is only available with the `serde` feature.
+///
+/// # Example
+/// ```no_run
+/// # use bevy::prelude::*;
+/// use bevy_event_set::*;
+/// # use serde_crate as serde;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// # #[serde(crate = "serde_crate")]
+/// struct Jump;
+/// #[derive(Serialize)]
+/// # #[serde(crate = "serde_crate")]
+/// struct Move(f32, f32);
+/// event_set!(InputEvents { Jump, Move });
+///
+/// let recorder = EventSetRecorder::<InputEvents>::create("input.ron", RecordFormat::Ron)
+///     .expect("could not create the recording");
+///
+/// App::build()
+///     .add_event_set::<InputEvents>()
+///     .add_plugin(recorder);
+/// ```
+pub struct EventSetRecorder<S> {
+	writer: Mutex<Option<RecordWriter>>,
+	marker: PhantomData<fn() -> S>,
+}
+
+impl<S> EventSetRecorder<S> {
+	/// Creates a recorder that writes to a new file, replacing it if it exists
+	pub fn create<P: AsRef<Path>>(path: P, format: RecordFormat) -> io::Result<Self> {
+		Ok(EventSetRecorder::to_writer(
+			BufWriter::new(File::create(path)?),
+			format,
+		))
+	}
+
+	/// Creates a recorder that writes to the given writer
+	pub fn to_writer<W: Write + Send + Sync + 'static>(writer: W, format: RecordFormat) -> Self {
+		EventSetRecorder {
+			writer: Mutex::new(Some(RecordWriter {
+				writer: Box::new(writer),
+				format,
+				frame: 0,
+				order: 0,
+			})),
+			marker: PhantomData,
+		}
+	}
+}
+
+impl<S: RecordEventSet + 'static> Plugin for EventSetRecorder<S> {
+	fn build(&self, app: &mut AppBuilder) {
+		let writer = self
+			.writer
+			.lock()
+			.unwrap()
+			.take()
+			.expect("an event set recorder can only be added to one app");
+
+		S::add_record_system(app, writer);
+	}
+}
+
+/// The format that an event set recording is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+	/// One RON entry per line
+	Ron,
+
+	/// One JSON object per line
+	Json,
+
+	/// Compact binary entries, written one after another with `bincode`
+	Binary,
+}
+
+/// An event in a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "serde_crate")]
+pub struct Recorded<E> {
+	/// The frame the event was sent in, `0` being the first frame of the recording
+	pub frame: u64,
+
+	/// The place of the event in the send order of the whole recording
+	pub order: u64,
+
+	/// The event, as a variant of the `[name]Any` enum of the event set
+	pub event: E,
+}
+
+/// Adds a system that records the events of an event set
+///
+/// Only implemented for event sets where all event types implement `Serialize`.
+#[doc(hidden)]
+pub trait RecordEventSet {
+	fn add_record_system(app: &mut AppBuilder, writer: RecordWriter);
+}
+
+/// Writes the events of one event set to a recording
+#[doc(hidden)]
+pub struct RecordWriter {
+	writer: Box<dyn Write + Send + Sync>,
+	format: RecordFormat,
+	frame: u64,
+	order: u64,
+}
+
+impl RecordWriter {
+	pub fn write<E: Serialize>(&mut self, event: E) {
+		let recorded = Recorded {
+			frame: self.frame,
+			order: self.order,
+			event,
+		};
+		self.order += 1;
+
+		let result = match self.format {
+			RecordFormat::Ron => ron::ser::to_string(&recorded)
+				.map_err(|e| e.to_string())
+				.and_then(|line| writeln!(self.writer, "{}", line).map_err(|e| e.to_string())),
+			RecordFormat::Json => serde_json::to_writer(&mut self.writer, &recorded)
+				.map_err(|e| e.to_string())
+				.and_then(|_| writeln!(self.writer).map_err(|e| e.to_string())),
+			RecordFormat::Binary => {
+				bincode::serialize_into(&mut self.writer, &recorded).map_err(|e| e.to_string())
+			}
+		};
+
+		if let Err(e) = result {
+			error!("could not record event: {}", e);
+		}
+	}
+
+	/// Moves the recording on to the next frame
+	pub fn end_frame(&mut self) {
+		self.frame += 1;
+		if let Err(e) = self.writer.flush() {
+			error!("could not write recording: {}", e);
+		}
+	}
+}
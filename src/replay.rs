@@ -0,0 +1,134 @@
+//! Replaying a recording of an event set
+//!
+//! See [`EventSetReplay`] for the documentation.
+
+use crate::{RecordFormat, Recorded, SendAnyEvent};
+use bevy::app::{AppBuilder, Plugin};
+use serde_crate::de::DeserializeOwned;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Sends the events of a recording made by an
+/// [`EventSetRecorder`](crate::EventSetRecorder) again, at the same frames
+///
+/// The events of each frame are sent at the start of that frame, in the order
+/// they were recorded. All event types in the set must implement
+/// `Deserialize`. Add the event set to the app before adding the replay.
+///
+/// The replay can also drop the events that are sent to the set by the app
+/// itself until all recorded events have been sent, so the recording is the
+/// only input. This covers events sent through the event set, its
+/// [`Only`](crate::Only) views, resources and commands, but not events sent
+/// straight to an event buffer.
+///
+/// This is only available with the `serde` feature.
+///
+/// # Example
+/// ```no_run
+/// # use bevy::prelude::*;
+/// use bevy_event_set::*;
+/// # use serde_crate as serde;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// # #[serde(crate = "serde_crate")]
+/// struct Jump;
+/// #[derive(Deserialize)]
+/// # #[serde(crate = "serde_crate")]
+/// struct Move(f32, f32);
+/// event_set!(InputEvents { Jump, Move });
+///
+/// let replay = EventSetReplay::<InputEvents>::open("input.ron", RecordFormat::Ron)
+///     .expect("could not read the recording")
+///     .suppress_live(true);
+///
+/// App::build()
+///     .add_event_set::<InputEvents>()
+///     .add_plugin(replay);
+/// ```
+pub struct EventSetReplay<S: SendAnyEvent> {
+	recording: Mutex<Option<Vec<Recorded<S::Any>>>>,
+	suppress_live: bool,
+}
+
+impl<S: SendAnyEvent> EventSetReplay<S>
+where
+	S::Any: DeserializeOwned,
+{
+	/// Reads a recording from a file
+	pub fn open<P: AsRef<Path>>(path: P, format: RecordFormat) -> io::Result<Self> {
+		EventSetReplay::from_reader(BufReader::new(File::open(path)?), format)
+	}
+
+	/// Reads a recording from the given reader
+	pub fn from_reader<R: Read>(mut reader: R, format: RecordFormat) -> io::Result<Self> {
+		let mut data = Vec::new();
+		reader.read_to_end(&mut data)?;
+
+		let recording = match format {
+			RecordFormat::Ron => String::from_utf8(data)
+				.map_err(invalid_data)?
+				.lines()
+				.filter(|line| !line.trim().is_empty())
+				.map(|line| ron::de::from_str(line).map_err(invalid_data))
+				.collect::<io::Result<_>>()?,
+			RecordFormat::Json => serde_json::Deserializer::from_slice(&data)
+				.into_iter()
+				.map(|recorded| recorded.map_err(invalid_data))
+				.collect::<io::Result<_>>()?,
+			RecordFormat::Binary => {
+				let mut data = data.as_slice();
+				let mut recording = Vec::new();
+				while !data.is_empty() {
+					recording.push(bincode::deserialize_from(&mut data).map_err(invalid_data)?);
+				}
+				recording
+			}
+		};
+
+		Ok(EventSetReplay {
+			recording: Mutex::new(Some(recording)),
+			suppress_live: false,
+		})
+	}
+}
+
+impl<S: SendAnyEvent> EventSetReplay<S> {
+	/// Drops the events that the app sends to the event set until the replay has finished
+	pub fn suppress_live(mut self, suppress: bool) -> Self {
+		self.suppress_live = suppress;
+		self
+	}
+}
+
+impl<S: ReplayEventSet + 'static> Plugin for EventSetReplay<S>
+where
+	S::Any: Send,
+{
+	fn build(&self, app: &mut AppBuilder) {
+		let recording = self
+			.recording
+			.lock()
+			.unwrap()
+			.take()
+			.expect("an event set replay can only be added to one app");
+
+		S::add_replay_system(app, recording, self.suppress_live);
+	}
+}
+
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(error: E) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Adds a system that sends the events of a recording to an event set
+#[doc(hidden)]
+pub trait ReplayEventSet: SendAnyEvent {
+	fn add_replay_system(
+		app: &mut AppBuilder,
+		recording: Vec<Recorded<Self::Any>>,
+		suppress_live: bool,
+	);
+}
@@ -0,0 +1,91 @@
+//! Event sets borrowed straight from the app resources
+//!
+//! See [`EventSetMut`] for the documentation.
+
+use crate::__private::{EventSetChannel, SendAnyToResources, SendToResources};
+use crate::{EventSet, EventSetSender, SendAnyEvent, SendEvent};
+use bevy::ecs::{Component, Resources};
+use std::marker::PhantomData;
+
+/// An event set that sends events straight to the app resources
+///
+/// This can be used where the event set can't be a system parameter, such as
+/// in exclusive systems, startup code or tests. Get one through
+/// [`ResourcesEventSetExt::event_set`].
+///
+/// # Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct EventOne;
+/// struct EventTwo;
+/// event_set!(MyEvents { EventOne, EventTwo });
+///
+/// fn level_loader_system(_world: &mut World, resources: &mut Resources) {
+///     let mut events = resources.event_set::<MyEvents>();
+///     events.send(EventOne);
+///     events.send(EventTwo);
+/// }
+/// ```
+pub struct EventSetMut<'r, S> {
+	resources: &'r Resources,
+	marker: PhantomData<fn() -> S>,
+}
+
+/// Trait used to add `event_set` and `event_set_sender` to the Bevy resources
+pub trait ResourcesEventSetExt {
+	/// Borrows an event set from the resources
+	///
+	/// Sending an event panics if the event set was not added to the app.
+	fn event_set<S: EventSet>(&self) -> EventSetMut<'_, S>;
+
+	/// Creates a sender that other threads can use to send events into an event set
+	///
+	/// Panics if the event set was not added to the app.
+	fn event_set_sender<S: SendAnyEvent>(&self) -> EventSetSender<S::Any>
+	where
+		S::Any: Component;
+}
+
+impl ResourcesEventSetExt for Resources {
+	fn event_set<S: EventSet>(&self) -> EventSetMut<'_, S> {
+		EventSetMut {
+			resources: self,
+			marker: PhantomData,
+		}
+	}
+
+	fn event_set_sender<S: SendAnyEvent>(&self) -> EventSetSender<S::Any>
+	where
+		S::Any: Component,
+	{
+		self.get::<EventSetChannel<S::Any>>()
+			.expect("no event set channel, was the event set added to the app?")
+			.sender()
+	}
+}
+
+impl<'r, S: SendToResources<T>, T> SendEvent<T> for EventSetMut<'r, S> {
+	fn send(&mut self, event: T) {
+		S::send_batch(self.resources, Some(event));
+	}
+
+	fn send_batch<E: IntoIterator<Item = T>>(&mut self, events: E) {
+		S::send_batch(self.resources, events);
+	}
+}
+
+impl<'r, S: SendToResources<T>, T> Extend<T> for EventSetMut<'r, S> {
+	fn extend<E: IntoIterator<Item = T>>(&mut self, events: E) {
+		S::send_batch(self.resources, events);
+	}
+}
+
+impl<'r, S: SendAnyToResources> SendAnyEvent for EventSetMut<'r, S> {
+	type Any = S::Any;
+
+	fn send_any(&mut self, event: S::Any) {
+		S::send_any(self.resources, event);
+	}
+}
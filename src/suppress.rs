@@ -0,0 +1,31 @@
+//! Dropping the events sent by the app while a recording is replayed
+
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Drops the events sent to the event set with sum enum `A` while a recording is replayed into it
+pub struct ReplaySuppression<A> {
+	active: AtomicBool,
+	marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Default for ReplaySuppression<A> {
+	fn default() -> Self {
+		ReplaySuppression {
+			active: AtomicBool::new(true),
+			marker: PhantomData,
+		}
+	}
+}
+
+impl<A> ReplaySuppression<A> {
+	/// Lets events through again once the replay has finished
+	pub fn finish(&self) {
+		self.active.store(false, Ordering::Relaxed);
+	}
+}
+
+/// Checks if events sent to the event set with sum enum `A` should be dropped
+pub fn is_suppressed<A>(suppression: Option<&ReplaySuppression<A>>) -> bool {
+	matches!(suppression, Some(suppression) if suppression.active.load(Ordering::Relaxed))
+}
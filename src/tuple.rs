@@ -0,0 +1,163 @@
+//! Event sets made from a tuple of event types
+//!
+//! See [`EventSetOf`] for the documentation.
+
+use crate::{EventSet, EventSetConfig, SendEvent};
+use bevy::app::{AppBuilder, Events};
+use bevy::ecs::{Component, ResMut, Resources, SystemParam, SystemState, World};
+
+/// An event set made from a tuple of up to 16 event types
+///
+/// This works like a set created with [`event_set!`](crate::event_set), but
+/// doesn't need to be declared first. It can be written inline in a system
+/// signature, or used by library code that takes a set of events as a type
+/// parameter.
+///
+/// # Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_event_set::*;
+///
+/// struct EventOne;
+/// struct EventTwo;
+///
+/// fn event_emitter_system(mut events: EventSetOf<(EventOne, EventTwo)>) {
+///     events.send(EventOne);
+///     events.send(EventTwo);
+/// }
+///
+/// App::build().add_event_set::<EventSetOf<(EventOne, EventTwo)>>();
+/// ```
+///
+/// The [`SendEvent`] impls of a tuple set are told apart by the position of
+/// the event type in the tuple, so generic code needs an extra type parameter
+/// for it:
+///
+/// ```
+/// use bevy_event_set::*;
+///
+/// struct Damage(u32);
+///
+/// fn hit<S: SendEvent<Damage, I>, I>(events: &mut S) {
+///     events.send(Damage(10));
+/// }
+/// ```
+pub struct EventSetOf<'a, T: EventTuple<'a>> {
+	buffers: T::Buffers,
+}
+
+/// A tuple of event types that can be used in an [`EventSetOf`]
+///
+/// This is implemented for tuples of up to 16 event types.
+pub trait EventTuple<'a> {
+	/// The event buffers of each type in the tuple
+	type Buffers: SystemParam;
+
+	/// Adds each event type in the tuple to the app
+	fn apply_with(app: &mut AppBuilder, config: &EventSetConfig);
+}
+
+impl<'a, T: EventTuple<'a>> EventSet for EventSetOf<'a, T> {
+	fn apply_with(app: &mut AppBuilder, config: &EventSetConfig) {
+		T::apply_with(app, config);
+	}
+}
+
+impl<'a, T: EventTuple<'a>> SystemParam for EventSetOf<'a, T> {
+	fn init(system_state: &mut SystemState, world: &World, resources: &mut Resources) {
+		T::Buffers::init(system_state, world, resources);
+	}
+
+	unsafe fn get_param(
+		system_state: &SystemState,
+		world: &World,
+		resources: &Resources,
+	) -> Option<Self> {
+		Some(EventSetOf {
+			buffers: T::Buffers::get_param(system_state, world, resources)?,
+		})
+	}
+}
+
+macro_rules! index {
+	($($index:ident $position:literal),*) => {
+		paste::paste! {
+			$(
+				#[doc = "Marks the event type at position " $position " of an [`EventSetOf`] tuple"]
+				pub enum $index {}
+			)*
+		}
+	};
+}
+
+index!(
+	I0 "0", I1 "1", I2 "2", I3 "3", I4 "4", I5 "5", I6 "6", I7 "7",
+	I8 "8", I9 "9", I10 "10", I11 "11", I12 "12", I13 "13", I14 "14", I15 "15"
+);
+
+macro_rules! event_tuple {
+	($(($index:ident $event:ident $field:tt)),*) => {
+		event_tuple!(@impl [$($event)*] $(($index $event $field))*);
+	};
+
+	(@impl $all:tt $(($index:ident $event:ident $field:tt))*) => {
+		impl<'a, $($event: Component),*> EventTuple<'a> for ($($event,)*) {
+			type Buffers = ($(ResMut<'a, Events<$event>>,)*);
+
+			fn apply_with(app: &mut AppBuilder, config: &EventSetConfig) {
+				$(crate::__private::add_event::<$event>(app, config);)*
+			}
+		}
+
+		$(event_tuple!(@send $all $index $event $field);)*
+	};
+
+	(@send [$($all:ident)*] $index:ident $event:ident $field:tt) => {
+		impl<'a, $($all: Component),*> SendEvent<$event, $index> for EventSetOf<'a, ($($all,)*)> {
+			fn send(&mut self, event: $event) {
+				self.buffers.$field.send(event);
+			}
+		}
+	};
+}
+
+event_tuple!((I0 A 0));
+event_tuple!((I0 A 0), (I1 B 1));
+event_tuple!((I0 A 0), (I1 B 1), (I2 C 2));
+event_tuple!((I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3));
+event_tuple!((I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4));
+event_tuple!((I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4), (I5 F 5));
+event_tuple!((I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4), (I5 F 5), (I6 G 6));
+event_tuple!((I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4), (I5 F 5), (I6 G 6), (I7 H 7));
+event_tuple!(
+	(I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4), (I5 F 5), (I6 G 6), (I7 H 7),
+	(I8 J 8)
+);
+event_tuple!(
+	(I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4), (I5 F 5), (I6 G 6), (I7 H 7),
+	(I8 J 8), (I9 K 9)
+);
+event_tuple!(
+	(I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4), (I5 F 5), (I6 G 6), (I7 H 7),
+	(I8 J 8), (I9 K 9), (I10 L 10)
+);
+event_tuple!(
+	(I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4), (I5 F 5), (I6 G 6), (I7 H 7),
+	(I8 J 8), (I9 K 9), (I10 L 10), (I11 M 11)
+);
+event_tuple!(
+	(I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4), (I5 F 5), (I6 G 6), (I7 H 7),
+	(I8 J 8), (I9 K 9), (I10 L 10), (I11 M 11), (I12 N 12)
+);
+event_tuple!(
+	(I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4), (I5 F 5), (I6 G 6), (I7 H 7),
+	(I8 J 8), (I9 K 9), (I10 L 10), (I11 M 11), (I12 N 12), (I13 O 13)
+);
+event_tuple!(
+	(I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4), (I5 F 5), (I6 G 6), (I7 H 7),
+	(I8 J 8), (I9 K 9), (I10 L 10), (I11 M 11), (I12 N 12), (I13 O 13), (I14 P 14)
+);
+event_tuple!(
+	(I0 A 0), (I1 B 1), (I2 C 2), (I3 D 3), (I4 E 4), (I5 F 5), (I6 G 6), (I7 H 7),
+	(I8 J 8), (I9 K 9), (I10 L 10), (I11 M 11), (I12 N 12), (I13 O 13), (I14 P 14), (I15 Q 15)
+);